@@ -0,0 +1,359 @@
+//! Simulates saved circuits without a window, for scripts and CI.
+//!
+//! Exits with 1 when a test fails and 2 when the circuit can't be simulated at all.
+
+use std::{env, fs, path::Path, process::ExitCode};
+
+use electron_architect_core::{
+    formats::{
+        self, save,
+        vcd::{Recorder, MAX_RECORDED_CHANGES},
+        vectors::{self, TestVectors},
+        ExportOptions,
+    },
+    graph::{node::NodeId, sim::Simulation, Graph},
+    plugins,
+};
+
+const USAGE: &str = "\
+Usage:
+  ea-sim run <circuit> [--ticks N] [--set NAME=0|1]... [NAME...]
+      Prints named outputs, or the nodes given, after N ticks
+  ea-sim table <circuit> [NAME...]
+      Prints the truth table of the named outputs, or the nodes given
+  ea-sim vcd <circuit> --ticks N [--set NAME=0|1]... [NAME...]
+      Prints every node, or the nodes given, as a VCD waveform
+  ea-sim test <script> [circuit]
+      Runs a test vector script, against its own circuit unless one is given
+  ea-sim export <circuit> <file>
+      Writes the circuit in the format the file's extension names, images included";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    Run,
+    Table,
+    Vcd,
+    Test,
+    Export,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    mode: Mode,
+    /// The circuit, or the script when testing
+    path: String,
+    ticks: Option<u64>,
+    /// Inputs held for the whole run
+    inputs: Vec<(String, bool)>,
+    /// Nodes to print, the circuit when testing or the file to export to
+    names: Vec<String>,
+}
+
+fn parse_input(assignment: &str) -> Result<(String, bool), String> {
+    match assignment.split_once('=') {
+        Some((name, "0")) => Ok((name.to_string(), false)),
+        Some((name, "1")) => Ok((name.to_string(), true)),
+        _ => Err(format!("Expected NAME=0 or NAME=1, not `{assignment}`")),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mode = match args.next().as_deref() {
+        Some("run") => Mode::Run,
+        Some("table") => Mode::Table,
+        Some("vcd") => Mode::Vcd,
+        Some("test") => Mode::Test,
+        Some("export") => Mode::Export,
+        Some(other) => return Err(format!("Unknown command `{other}`")),
+        None => return Err("Expected a command".to_string()),
+    };
+    let path = args.next().ok_or("Expected a file")?;
+
+    let mut parsed = Args {
+        mode,
+        path,
+        ticks: None,
+        inputs: Vec::new(),
+        names: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ticks" => {
+                let ticks = args.next().and_then(|ticks| ticks.parse().ok());
+                parsed.ticks = Some(ticks.ok_or("Expected a number of ticks after `--ticks`")?);
+            }
+            "--set" => {
+                let assignment = args.next().ok_or("Expected NAME=0|1 after `--set`")?;
+                parsed.inputs.push(parse_input(&assignment)?);
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option `{flag}`")),
+            _ => parsed.names.push(arg),
+        }
+    }
+
+    let simulates = matches!(parsed.mode, Mode::Run | Mode::Vcd);
+    if !simulates && (parsed.ticks.is_some() || !parsed.inputs.is_empty()) {
+        return Err("Only `run` and `vcd` take `--ticks` or `--set`".to_string());
+    }
+    if parsed.mode == Mode::Vcd && parsed.ticks.is_none() {
+        return Err("`vcd` needs to know how many `--ticks` to record".to_string());
+    }
+    if parsed.mode == Mode::Test && parsed.names.len() > 1 {
+        return Err("`test` takes a script and at most one circuit".to_string());
+    }
+    if parsed.mode == Mode::Export && parsed.names.len() != 1 {
+        return Err("`export` takes a circuit and a file to write".to_string());
+    }
+    Ok(parsed)
+}
+
+/// Save files are read natively, anything else is imported, warning about what was left out.
+fn load_circuit(path: &str) -> Result<Graph, String> {
+    if Path::new(path).extension().is_some_and(|ext| ext == "ea") {
+        return save::load_file(path);
+    }
+    let (graph, warnings) = formats::import_file(path)?;
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+    Ok(graph)
+}
+
+fn find_nodes(graph: &Graph, names: &[String]) -> Result<Vec<NodeId>, String> {
+    names
+        .iter()
+        .map(|name| {
+            graph
+                .find_node_by_name(name)
+                .ok_or_else(|| format!("No node named `{name}`"))
+        })
+        .collect()
+}
+
+/// The nodes named on the command line, otherwise `default`.
+fn chosen_nodes(
+    graph: &Graph,
+    names: &[String],
+    default: Vec<NodeId>,
+) -> Result<Vec<NodeId>, String> {
+    match names.is_empty() {
+        true => Ok(default),
+        false => find_nodes(graph, names),
+    }
+}
+
+fn simulate(graph: &Graph, args: &Args, recorder: Option<Recorder>) -> Result<Simulation, String> {
+    let ticks = match args.ticks {
+        Some(ticks) => ticks,
+        None => graph
+            .settle_ticks()
+            .ok_or("The circuit has loops, so it needs to be given `--ticks`")?,
+    };
+    let mut simulation = Simulation::new();
+    simulation.recorder = recorder;
+    for (name, value) in &args.inputs {
+        let id = graph
+            .find_node_by_name(name)
+            .ok_or_else(|| format!("No node named `{name}`"))?;
+        simulation.drive(id, *value);
+    }
+    for _ in 0..ticks {
+        simulation.step(graph);
+    }
+    Ok(simulation)
+}
+
+/// What to print, and whether every test passed.
+fn execute(args: &Args) -> Result<(String, bool), String> {
+    // So that circuits using them can be loaded
+    plugins::register_all()?;
+    if args.mode == Mode::Test {
+        let report = match args.names.first() {
+            Some(circuit) => {
+                let s = fs::read_to_string(&args.path)
+                    .map_err(|e| format!("Couldn't read `{}`: {e}", args.path))?;
+                let script: TestVectors = s.parse().map_err(|e| format!("`{}`: {e}", args.path))?;
+                script.run(&load_circuit(circuit)?)?
+            }
+            None => vectors::run_file(&args.path, &Graph::new())?,
+        };
+        return Ok((format!("{report}\n"), report.passed()));
+    }
+
+    let graph = load_circuit(&args.path)?;
+    let out = match args.mode {
+        Mode::Run => {
+            let nodes = chosen_nodes(&graph, &args.names, graph.output_ports())?;
+            let simulation = simulate(&graph, args, None)?;
+            nodes
+                .into_iter()
+                .map(|id| {
+                    let value = simulation.output(id);
+                    format!("{}={}\n", graph.node_label(id), value as u8)
+                })
+                .collect()
+        }
+        Mode::Table => {
+            let outputs = chosen_nodes(&graph, &args.names, graph.output_ports())?;
+            graph
+                .truth_table(&graph.input_ports(), &outputs)?
+                .to_string()
+        }
+        Mode::Vcd => {
+            let all = graph.nodes().map(|(id, _)| id).collect();
+            let signals = chosen_nodes(&graph, &args.names, all)?;
+            let recorder = Recorder::new(signals, MAX_RECORDED_CHANGES);
+            let simulation = simulate(&graph, args, Some(recorder))?;
+            let recorder = simulation.recorder.unwrap(); // Put there above
+            if recorder.len() == MAX_RECORDED_CHANGES {
+                eprintln!("warning: Recording was full, so only the latest changes were kept");
+            }
+            recorder.to_vcd(&graph, "graph")
+        }
+        Mode::Export => {
+            formats::export_file(&graph, &args.names[0], &ExportOptions::default())?;
+            String::new()
+        }
+        Mode::Test => unreachable!("Handled above"),
+    };
+    Ok((out, true))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match execute(&args) {
+        Ok((out, passed)) => {
+            print!("{out}");
+            match passed {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::from(1),
+            }
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const HALF_ADDER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/formats/golden/half_adder.ea"
+    );
+
+    /// A path in the temporary directory that's removed once the test is done with it,
+    /// unique to this run so tests running at the same time don't share files.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("ea-sim-{}-{name}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn args(s: &str) -> Result<Args, String> {
+        parse_args(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            args("run adder.ea --ticks 4 --set a=1 sum --set b=0"),
+            Ok(Args {
+                mode: Mode::Run,
+                path: "adder.ea".to_string(),
+                ticks: Some(4),
+                inputs: vec![("a".to_string(), true), ("b".to_string(), false)],
+                names: vec!["sum".to_string()],
+            })
+        );
+        assert!(args("").is_err());
+        assert!(args("run").is_err());
+        assert!(args("walk adder.ea").is_err());
+        assert!(args("run adder.ea --ticks").is_err());
+        assert!(args("run adder.ea --set a=2").is_err());
+        assert!(args("run adder.ea --verbose").is_err());
+        assert!(args("table adder.ea --ticks 3").is_err());
+        assert!(args("vcd adder.ea").is_err());
+        assert!(args("test adder.tv adder.ea adder.blif").is_err());
+        assert!(args("export adder.ea").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let (out, passed) =
+            execute(&args(&format!("run {HALF_ADDER} --set a=1 --set b=1")).unwrap()).unwrap();
+        assert!(passed);
+        assert_eq!(out, "sum=0\nand=0\n");
+        let (out, _) =
+            execute(&args(&format!("run {HALF_ADDER} --set a=1 sum carry")).unwrap()).unwrap();
+        assert_eq!(out, "sum=1\ncarry=0\n");
+    }
+
+    #[test]
+    fn test_table() {
+        let (out, _) = execute(&args(&format!("table {HALF_ADDER} sum")).unwrap()).unwrap();
+        assert_eq!(out.lines().count(), 5);
+    }
+
+    #[test]
+    fn test_vcd() {
+        let (out, _) =
+            execute(&args(&format!("vcd {HALF_ADDER} --ticks 3 --set a=1 sum")).unwrap()).unwrap();
+        assert!(out.contains("$var wire 1"));
+        assert!(out.contains("#1"));
+    }
+
+    #[test]
+    fn test_export() {
+        let image = TempFile::new("half_adder.png");
+        let (out, _) =
+            execute(&args(&format!("export {HALF_ADDER} {}", image.0.display())).unwrap()).unwrap();
+        assert_eq!(out, "");
+        assert!(fs::read(&image.0).unwrap().starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_plugin_gates() {
+        let circuit = TempFile::new("majority.ea");
+        fs::write(
+            &circuit.0,
+            "electron-architect-rust 1\nnode 0 0 always\nnode 0 2 always\nnode 0 4 never\nnode 2 2 majority y\nwire 0 3 horz_s\nwire 1 3 horz_s\nwire 2 3 horz_s\n",
+        )
+        .unwrap();
+        let (out, _) =
+            execute(&args(&format!("run {} --ticks 2 y", circuit.0.display())).unwrap()).unwrap();
+        assert_eq!(out, "y=1\n");
+    }
+
+    #[test]
+    fn test_test() {
+        let script = HALF_ADDER.replace(".ea", ".tv");
+        let (out, passed) = execute(&args(&format!("test {script}")).unwrap()).unwrap();
+        assert!(passed, "{out}");
+
+        let failing = TempFile::new("failing.tv");
+        fs::write(&failing.0, "inputs a b\noutputs sum\n11 -> 1\n").unwrap();
+        let (out, passed) =
+            execute(&args(&format!("test {} {HALF_ADDER}", failing.0.display())).unwrap()).unwrap();
+        assert!(!passed);
+        assert!(out.starts_with("FAIL"));
+    }
+}
@@ -1,22 +1,15 @@
-use std::convert::From;
 use std::fmt::Display;
 
+#[cfg(feature = "raylib")]
 use raylib::prelude::*;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 pub struct Coords {
     pub x: i32,
     pub y: i32,
 }
 
 impl Coords {
-    pub fn default() -> Self {
-        Self {
-            x: i32::default(),
-            y: i32::default(),
-        }
-    }
-
     /// Size in world units of the space between gridlines (including the infinitely thin gridline itself)
     pub const GRID_SIZE: f32 = 16.0;
     pub const GRID_RADIUS: f32 = Self::GRID_SIZE / 2.0;
@@ -24,6 +17,7 @@ impl Coords {
     pub const GRID_RADIUS_INV: f32 = 1.0 / Self::GRID_RADIUS;
 
     /// Converts grid coordinates to world vector
+    #[cfg(feature = "raylib")]
     pub fn into_position(self) -> Vector2 {
         Vector2 {
             x: self.x as f32 * Self::GRID_SIZE + Self::GRID_RADIUS,
@@ -32,6 +26,7 @@ impl Coords {
     }
 
     /// Converts world vector to grid coordinates
+    #[cfg(feature = "raylib")]
     pub fn from_position(p: Vector2) -> Self {
         Self {
             x: ((p.x - Self::GRID_RADIUS) * Self::GRID_SIZE_INV).round() as i32,
@@ -71,6 +66,7 @@ impl Coords {
     }
 }
 
+#[cfg(feature = "raylib")]
 impl From<Vector2> for Coords {
     fn from(value: Vector2) -> Self {
         Self {
@@ -80,6 +76,7 @@ impl From<Vector2> for Coords {
     }
 }
 
+#[cfg(feature = "raylib")]
 impl From<Coords> for Vector2 {
     fn from(value: Coords) -> Self {
         Self {
@@ -138,10 +135,10 @@ mod tests {
                             let end = Coords { x: x2, y: y2 };
 
                             let inclusive_start = start.is_intersecting_coords(&start, &end);
-                            assert_eq!(inclusive_start, true, "test: {start}, start: {start}, end: {end}, expected: true, got: {inclusive_start}");
+                            assert!(inclusive_start, "test: {start}, start: {start}, end: {end}, expected: true, got: {inclusive_start}");
 
                             let inclusive_end = end.is_intersecting_coords(&start, &end);
-                            assert_eq!(inclusive_end, true, "test: {end}, start: {start}, end: {end}, expected: true, got: {inclusive_start}");
+                            assert!(inclusive_end, "test: {end}, start: {start}, end: {end}, expected: true, got: {inclusive_start}");
                         }
                     }
                 }
@@ -170,7 +167,7 @@ mod tests {
                             };
 
                             let overlapping = midpoint.is_intersecting_coords(&start, &end);
-                            assert_eq!(overlapping, true, "test: {midpoint}, start: {start}, end: {end}, expected: true, got: {overlapping}");
+                            assert!(overlapping, "test: {midpoint}, start: {start}, end: {end}, expected: true, got: {overlapping}");
 
                             // Perpendicular point
                             let perp: Coords;
@@ -196,7 +193,7 @@ mod tests {
                             }
 
                             let result = perp.is_intersecting_coords(&start, &end);
-                            assert_eq!(result, false, "test: {perp}, start: {start}, end: {end}, expected: false, got: {result}");
+                            assert!(!result, "test: {perp}, start: {start}, end: {end}, expected: false, got: {result}");
                         }
                     }
                 }
@@ -223,7 +220,7 @@ mod tests {
                             };
 
                             let overlapping1 = after_end.is_intersecting_coords(&start, &end);
-                            assert_eq!(overlapping1, false, "test: {after_end}, start: {start}, end: {end}, expected: false, got: {overlapping1}");
+                            assert!(!overlapping1, "test: {after_end}, start: {start}, end: {end}, expected: false, got: {overlapping1}");
 
                             let before_start = Coords {
                                 x: end.x + 2 * (start.x - end.x),
@@ -231,7 +228,7 @@ mod tests {
                             };
 
                             let overlapping2 = before_start.is_intersecting_coords(&start, &end);
-                            assert_eq!(overlapping2, false, "test: {before_start}, start: {start}, end: {end}, expected: false, got: {overlapping2}");
+                            assert!(!overlapping2, "test: {before_start}, start: {start}, end: {end}, expected: false, got: {overlapping2}");
                         }
                     }
                 }
@@ -0,0 +1,83 @@
+use std::{fs, path::Path};
+
+use crate::{coords::Coords, graph::Graph};
+
+pub mod blif;
+pub mod dot;
+pub mod image;
+pub mod legacy;
+pub mod logisim;
+pub mod netlist;
+pub mod save;
+pub mod vcd;
+pub mod vectors;
+pub mod verilog;
+pub mod vhdl;
+mod xml;
+
+/// Settings that only some export formats use.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportOptions {
+    /// Graphviz: group each separate circuit.
+    pub clustered: bool,
+    /// Images: pixels per canvas pixel, which needn't be whole.
+    pub scale: Option<f32>,
+    /// Images: only draw nodes between these corners.
+    pub region: Option<(Coords, Coords)>,
+}
+
+/// Writes `graph` in whichever format the extension of `path` names.
+/// HDL modules are named after the file.
+///
+/// The file is only replaced once it has been written in full.
+pub fn export_file(graph: &Graph, path: &str, options: &ExportOptions) -> Result<(), String> {
+    let path_ref = Path::new(path);
+    let stem = path_ref
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("circuit");
+    let extension = path_ref.extension().and_then(|ext| ext.to_str());
+    if options.clustered && !matches!(extension, Some("dot" | "gv")) {
+        return Err("Only Graphviz exports can be clustered".to_string());
+    }
+    if (options.scale.is_some() || options.region.is_some())
+        && !matches!(extension, Some("svg" | "png"))
+    {
+        return Err("Only images can be scaled or cropped".to_string());
+    }
+
+    let contents = match extension {
+        Some("dot" | "gv") => dot::export(graph, stem, options.clustered).into_bytes(),
+        Some("svg") => image::export_svg(graph, options)?.into_bytes(),
+        Some("png") => image::export_png(graph, options)?,
+        Some("v") => verilog::export(graph, stem)?.into_bytes(),
+        Some("vhd" | "vhdl") => vhdl::export(graph, stem)?.into_bytes(),
+        Some("blif") => blif::export(graph, stem)?.into_bytes(),
+        Some(ext) => return Err(format!("Can't export to `.{ext}` files")),
+        None => return Err("Expected a file extension to export to".to_string()),
+    };
+    let temp = format!("{path}.tmp");
+    fs::write(&temp, contents)
+        .and_then(|()| fs::rename(&temp, path))
+        .map_err(|e| {
+            // Whatever was there before is left alone
+            let _ = fs::remove_file(&temp);
+            format!("Couldn't write `{path}`: {e}")
+        })
+}
+
+/// Reads a graph from another tool's format, chosen by the extension of `path`,
+/// along with warnings about anything that was left out.
+///
+/// Save files from the original Electron Architect are recognised by their contents.
+pub fn import_file(path: &str) -> Result<(Graph, Vec<String>), String> {
+    let s = fs::read_to_string(path).map_err(|e| format!("Couldn't read `{path}`: {e}"))?;
+    let graph = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("blif") => blif::import(&s).map(|graph| (graph, Vec::new())),
+        Some("circ") => logisim::import(&s),
+        _ if legacy::is_legacy(&s) => legacy::load(&s),
+        Some(ext) => return Err(format!("Can't import `.{ext}` files")),
+        None => return Err("Expected a file extension to import from".to_string()),
+    };
+    graph.map_err(|e| format!("`{path}`: {e}"))
+}
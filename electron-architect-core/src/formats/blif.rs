@@ -0,0 +1,540 @@
+//! Berkeley Logic Interchange Format, as read and written by ABC and Yosys.
+//!
+//! Only combinational models are supported: `.inputs`, `.outputs` and `.names` covers.
+//! Imported covers that match a single gate become that gate,
+//! the rest are minimised into a sum-of-products.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{
+    coords::Coords,
+    formats::netlist::{Driver, Netlist},
+    graph::{
+        expr::Expr,
+        gate::{Gate, Gate0, Gate1, GateN},
+        minimise::minimise,
+        node::NodeId,
+        synth::{choose_elbow, COLUMN_SPACING, ROW_SPACING},
+        truth_table::MAX_TRUTH_TABLE_INPUTS,
+        Graph,
+    },
+};
+
+/// A `.names` block: the output is `value` whenever the inputs match any of the patterns.
+struct Cover {
+    inputs: Vec<String>,
+    output: String,
+    patterns: Vec<String>,
+    value: Option<bool>,
+}
+
+impl Cover {
+    fn add_row(&mut self, first: &str, second: Option<&str>) -> Result<(), String> {
+        let (pattern, value) = match (self.inputs.is_empty(), second) {
+            (true, None) => ("", first),
+            (false, Some(value)) => (first, value),
+            _ => return Err("Wrong number of fields in cover row".to_string()),
+        };
+        if pattern.len() != self.inputs.len() || !pattern.chars().all(|ch| "01-".contains(ch)) {
+            return Err(format!("Bad input pattern `{pattern}`"));
+        }
+        let value = match value {
+            "0" => false,
+            "1" => true,
+            _ => return Err(format!("Bad output value `{value}`")),
+        };
+        if self.value.is_some_and(|existing| existing != value) {
+            return Err("Cover rows disagree on the output value".to_string());
+        }
+        self.value = Some(value);
+        self.patterns.push(pattern.to_string());
+        Ok(())
+    }
+
+    /// Whether any pattern matches `minterm`, whose first input is the most significant bit.
+    fn matches(&self, minterm: u32) -> bool {
+        let n = self.inputs.len();
+        self.patterns.iter().any(|pattern| {
+            pattern.bytes().enumerate().all(|(i, ch)| {
+                let bit = minterm >> (n - 1 - i) & 1 == 1;
+                ch == b'-' || bit == (ch == b'1')
+            })
+        })
+    }
+
+    fn expr(&self) -> Result<Expr, String> {
+        let n = self.inputs.len();
+        if n > MAX_TRUTH_TABLE_INPUTS {
+            return Err(format!(
+                "`{}` has {n} inputs, the limit is {MAX_TRUTH_TABLE_INPUTS}",
+                self.output
+            ));
+        }
+        // With no rows at all the output is constant 0
+        let on_set = self.value.unwrap_or(true);
+        let minterms: Vec<u32> = (0..1u32 << n)
+            .filter(|&minterm| self.matches(minterm) == on_set)
+            .collect();
+
+        let vars: Vec<Expr> = self.inputs.iter().cloned().map(Expr::Var).collect();
+        if n >= 2 {
+            let gate = GateN::ALL
+                .into_iter()
+                .find(|gate| gate_minterms(*gate, n) == minterms);
+            if let Some(gate) = gate {
+                return Ok(Expr::Gate(gate, vars));
+            }
+        }
+        Ok(minimise(&self.inputs, &minterms))
+    }
+}
+
+fn gate_minterms(gate: GateN, n: usize) -> Vec<u32> {
+    (0..1u32 << n)
+        .filter(|&minterm| {
+            let inputs: Vec<bool> = (0..n).map(|i| minterm >> (n - 1 - i) & 1 == 1).collect();
+            gate.evaluate(&inputs)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Model {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    covers: Vec<Cover>,
+}
+
+/// Strips comments and joins `\` continuations, keeping the number of each line's first line.
+fn logical_lines(s: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (i, line) in s.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        let (line, continued) = match line.trim_end().strip_suffix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let (start, mut text) = pending.take().unwrap_or((i + 1, String::new()));
+        text.push_str(line);
+        text.push(' ');
+        match continued {
+            true => pending = Some((start, text)),
+            false => lines.push((start, text)),
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+fn parse(s: &str) -> Result<Model, String> {
+    let mut model = Model::default();
+    let mut in_cover = false;
+    for (line_number, line) in logical_lines(s) {
+        let mut fields = line.split_whitespace();
+        let Some(first) = fields.next() else {
+            continue;
+        };
+        let result = match first {
+            ".end" => break,
+            ".model" => Ok(()),
+            ".inputs" => {
+                model.inputs.extend(fields.map(String::from));
+                Ok(())
+            }
+            ".outputs" => {
+                model.outputs.extend(fields.map(String::from));
+                Ok(())
+            }
+            ".names" => {
+                let mut inputs: Vec<String> = fields.map(String::from).collect();
+                match inputs.pop() {
+                    Some(output) => {
+                        model.covers.push(Cover {
+                            inputs,
+                            output,
+                            patterns: Vec::new(),
+                            value: None,
+                        });
+                        Ok(())
+                    }
+                    None => Err("Expected an output after `.names`".to_string()),
+                }
+            }
+            directive if directive.starts_with('.') => {
+                Err(format!("`{directive}` isn't supported"))
+            }
+            _ => match model.covers.last_mut() {
+                Some(cover) if in_cover => cover.add_row(first, fields.next()),
+                _ => Err("Cover row outside of `.names`".to_string()),
+            },
+        };
+        result.map_err(|e| format!("Line {line_number}: {e}"))?;
+        in_cover = first == ".names" || !first.starts_with('.');
+    }
+    Ok(model)
+}
+
+/// Places nodes in columns by logic depth as they're created, inputs first.
+struct Importer<'m> {
+    graph: Graph,
+    covers: HashMap<&'m str, &'m Cover>,
+    signals: HashMap<&'m str, NodeId>,
+    in_progress: HashSet<&'m str>,
+    levels: Vec<i32>,
+    rows_used: HashMap<i32, i32>,
+}
+
+impl<'m> Importer<'m> {
+    fn place(&mut self, gate: Gate, inputs: &[NodeId]) -> NodeId {
+        let level = inputs
+            .iter()
+            .map(|input| self.levels[input.0] + 1)
+            .max()
+            .unwrap_or(0);
+        let row = self.rows_used.entry(level).or_insert(0);
+        let coords = Coords {
+            x: level * COLUMN_SPACING,
+            y: *row * ROW_SPACING,
+        };
+        *row += 1;
+
+        let id = self.graph.add_node(&gate, &coords);
+        for &input in inputs {
+            let start = self.graph.node(input).coords;
+            self.graph
+                .add_wire(input, id, &choose_elbow(&start, &coords));
+        }
+        self.levels.push(level);
+        id
+    }
+
+    fn build(&mut self, expr: &Expr, vars: &HashMap<&str, NodeId>) -> NodeId {
+        match expr {
+            Expr::Var(name) => vars[name.as_str()],
+            Expr::Const(value) => {
+                let gate = if *value { Gate0::Always } else { Gate0::Never };
+                self.place(Gate::G0(gate), &[])
+            }
+            Expr::Not(arg) => {
+                let arg = self.build(arg, vars);
+                self.place(Gate::GN(GateN::Nor), &[arg])
+            }
+            Expr::Gate(gate, args) => {
+                let args: Vec<NodeId> = args.iter().map(|arg| self.build(arg, vars)).collect();
+                self.place(Gate::GN(*gate), &args)
+            }
+        }
+    }
+
+    /// The node driving `name`, building its cover and everything it depends on first.
+    /// Covers are visited with a stack of their own, as chains of them can be very long.
+    fn signal(&mut self, name: &'m str) -> Result<NodeId, String> {
+        // Each cover is visited once to queue its inputs, then again to build it
+        let mut stack = vec![(name, false)];
+        while let Some((name, inputs_built)) = stack.pop() {
+            if self.signals.contains_key(name) {
+                continue;
+            }
+            let cover = *self
+                .covers
+                .get(name)
+                .ok_or_else(|| format!("Signal `{name}` is never driven"))?;
+            if !inputs_built {
+                if !self.in_progress.insert(name) {
+                    return Err(format!("Combinational loop through `{name}`"));
+                }
+                stack.push((name, true));
+                // Reversed, so inputs are built in order
+                stack.extend(
+                    cover
+                        .inputs
+                        .iter()
+                        .rev()
+                        .map(|input| (input.as_str(), false)),
+                );
+                continue;
+            }
+
+            let vars: HashMap<&str, NodeId> = (cover.inputs.iter())
+                .map(|input| (input.as_str(), self.signals[input.as_str()]))
+                .collect();
+            let id = match cover.expr()? {
+                // Each signal gets its own node, even if it just copies another
+                Expr::Var(var) => self.place(Gate::G1(Gate1::Buffer), &[vars[var.as_str()]]),
+                expr => self.build(&expr, &vars),
+            };
+            self.graph.node_mut(id).name = Some(name.to_string());
+            self.signals.insert(name, id);
+        }
+        Ok(self.signals[name])
+    }
+}
+
+pub fn import(s: &str) -> Result<Graph, String> {
+    let model = parse(s)?;
+    let mut importer = Importer {
+        graph: Graph::new(),
+        covers: HashMap::new(),
+        signals: HashMap::new(),
+        in_progress: HashSet::new(),
+        levels: Vec::new(),
+        rows_used: HashMap::new(),
+    };
+
+    for name in &model.inputs {
+        if importer.signals.contains_key(name.as_str()) {
+            return Err(format!("Input `{name}` is declared twice"));
+        }
+        let id = importer.place(Gate::G1(Gate1::Buffer), &[]);
+        importer.graph.node_mut(id).name = Some(name.clone());
+        importer.signals.insert(name, id);
+    }
+    for cover in &model.covers {
+        let output = cover.output.as_str();
+        if importer.signals.contains_key(output) || importer.covers.contains_key(output) {
+            return Err(format!("`{output}` is driven more than once"));
+        }
+        importer.covers.insert(output, cover);
+    }
+
+    // Outputs first so they're laid out in the order declared, then anything unused
+    let names = (model.outputs.iter()).chain(model.covers.iter().map(|cover| &cover.output));
+    for name in names {
+        importer.signal(name)?;
+    }
+    Ok(importer.graph)
+}
+
+/// Cover rows for a gate with `n` inputs, without the output column.
+/// Xor and xnor need half of every row, so are only written with two inputs.
+fn gate_patterns(gate: GateN, n: usize) -> Vec<String> {
+    let one_hot = |ch: char| -> Vec<String> {
+        (0..n)
+            .map(|i| (0..n).map(|j| if i == j { ch } else { '-' }).collect())
+            .collect()
+    };
+    match gate {
+        GateN::And => vec!["1".repeat(n)],
+        GateN::Nor => vec!["0".repeat(n)],
+        GateN::Nand => one_hot('0'),
+        GateN::Or => one_hot('1'),
+        GateN::Xor | GateN::Xnor => gate_minterms(gate, n)
+            .into_iter()
+            .map(|minterm| format!("{minterm:0n$b}"))
+            .collect(),
+    }
+}
+
+/// `base`, or `base` with a number after it, whichever isn't `taken` yet.
+fn fresh_name(taken: &mut HashSet<String>, base: &str) -> String {
+    let name = (0..)
+        .map(|i| match i {
+            0 => base.to_string(),
+            _ => format!("{base}_{i}"),
+        })
+        .find(|name| !taken.contains(name))
+        .unwrap(); // There are only so many names taken
+    taken.insert(name.clone());
+    name
+}
+
+pub fn export(graph: &Graph, model: &str) -> Result<String, String> {
+    let netlist = Netlist::build(graph, model, &[])?;
+    let mut out = String::new();
+
+    let port_names = |ports: &[NodeId]| -> Vec<&str> {
+        ports.iter().map(|&id| netlist.signal_name(id)).collect()
+    };
+    let mut taken: HashSet<String> = (netlist.signals.iter())
+        .map(|signal| signal.name.clone())
+        .collect();
+    writeln!(out, ".model {}", netlist.name).unwrap();
+    if !netlist.inputs.is_empty() {
+        writeln!(out, ".inputs {}", port_names(&netlist.inputs).join(" ")).unwrap();
+    }
+    if !netlist.outputs.is_empty() {
+        writeln!(out, ".outputs {}", port_names(&netlist.outputs).join(" ")).unwrap();
+    }
+
+    for signal in &netlist.signals {
+        let name = &signal.name;
+        match &signal.driver {
+            Driver::Input => {}
+            Driver::Const(value) => {
+                writeln!(out, ".names {name}").unwrap();
+                if *value {
+                    writeln!(out, "1").unwrap();
+                }
+            }
+            Driver::Buffer(input) => {
+                writeln!(out, ".names {} {name}", netlist.signal_name(*input)).unwrap();
+                writeln!(out, "1 1").unwrap();
+            }
+            Driver::Gate(gate @ (GateN::Xor | GateN::Xnor), inputs) if inputs.len() > 2 => {
+                // Parity of the inputs a pair at a time, then the gate itself on the last one
+                let (last, first) = inputs.split_last().unwrap();
+                let mut partial = netlist.signal_name(first[0]).to_string();
+                for (i, &input) in first.iter().enumerate().skip(1) {
+                    let next = fresh_name(&mut taken, &format!("{name}_xor{i}"));
+                    writeln!(
+                        out,
+                        ".names {partial} {} {next}",
+                        netlist.signal_name(input)
+                    )
+                    .unwrap();
+                    writeln!(out, "01 1\n10 1").unwrap();
+                    partial = next;
+                }
+                writeln!(
+                    out,
+                    ".names {partial} {} {name}",
+                    netlist.signal_name(*last)
+                )
+                .unwrap();
+                for pattern in gate_patterns(*gate, 2) {
+                    writeln!(out, "{pattern} 1").unwrap();
+                }
+            }
+            Driver::Gate(gate, inputs) => {
+                writeln!(out, ".names {} {name}", port_names(inputs).join(" ")).unwrap();
+                for pattern in gate_patterns(*gate, inputs.len()) {
+                    writeln!(out, "{pattern} 1").unwrap();
+                }
+            }
+        }
+    }
+
+    writeln!(out, ".end").unwrap();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::save,
+        graph::{
+            elbow::Elbow,
+            equiv::{check_equivalence, Equivalence},
+        },
+    };
+
+    #[test]
+    fn test_golden_half_adder() {
+        let graph = save::load(include_str!("golden/half_adder.ea")).unwrap();
+        assert_eq!(
+            export(&graph, "half_adder").unwrap(),
+            include_str!("golden/half_adder.blif")
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = save::load(include_str!("golden/half_adder.ea")).unwrap();
+        let imported = import(include_str!("golden/half_adder.blif")).unwrap();
+        assert_eq!(
+            check_equivalence(&original, &imported),
+            Ok(Equivalence::Equivalent)
+        );
+
+        let xor3 =
+            import(".inputs a b c\n.outputs y\n.names a b c y\n001 1\n010 1\n100 1\n111 1\n");
+        let xor3 = xor3.unwrap();
+        let y = xor3.find_node_by_name("y").unwrap();
+        assert_eq!(xor3.node(y).gate, Gate::GN(GateN::Xor));
+        let reimported = import(&export(&xor3, "xor3").unwrap()).unwrap();
+        assert_eq!(
+            check_equivalence(&xor3, &reimported),
+            Ok(Equivalence::Equivalent)
+        );
+    }
+
+    #[test]
+    fn test_import_recognises_gates() {
+        let graph = import(
+            "# Off-set cover of a nand, then an inverter
+.model gates
+.inputs a b
+.outputs y z
+.names a b \\
+  y
+11 0
+.names y z
+0 1
+.end",
+        )
+        .unwrap();
+        let gate_of = |name: &str| graph.node(graph.find_node_by_name(name).unwrap()).gate;
+        assert_eq!(gate_of("y"), Gate::GN(GateN::Nand));
+        assert_eq!(gate_of("z"), Gate::GN(GateN::Nor));
+        assert_eq!(graph.nodes().count(), 4);
+    }
+
+    #[test]
+    fn test_import_minimises_other_covers() {
+        let graph = import(".inputs a b c\n.outputs y\n.names a b c y\n11- 1\n1-1 1\n-11 1\n");
+        let graph = graph.unwrap();
+        let find = |name: &str| graph.find_node_by_name(name).unwrap();
+        let table = graph
+            .truth_table(&[find("a"), find("b"), find("c")], &[find("y")])
+            .unwrap();
+        assert_eq!(table.minterms(0), vec![3, 5, 6, 7]);
+
+        // Inputs in the first column, the three ands in the next, then the or
+        let xs: Vec<i32> = graph.nodes().map(|(_, node)| node.coords.x).collect();
+        assert_eq!(xs.iter().max(), Some(&(2 * COLUMN_SPACING)));
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(import(".inputs a\n.outputs y\n.latch a y re clk 0").is_err());
+        assert!(import(".outputs y\n.names a y\n1 1").is_err());
+        assert!(import(".outputs y\n.names z y\n1 1\n.names y z\n1 1").is_err());
+        assert!(import(".inputs a\n.names a y\n1 1\n.names a y\n0 1").is_err());
+        assert!(import(".inputs a b\n.names a b y\n1 1").is_err());
+        assert!(import(".inputs a b\n.names a b y\n11 1\n00 0").is_err());
+        assert!(import("11 1").is_err());
+    }
+
+    #[test]
+    fn test_wide_xor() {
+        for gate in [GateN::Xor, GateN::Xnor] {
+            let mut graph = Graph::new();
+            let y = graph.add_node(&Gate::GN(gate), &Coords { x: 4, y: 0 });
+            graph.node_mut(y).name = Some("y".to_string());
+            for i in 0..20 {
+                let input = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: i });
+                graph.node_mut(input).name = Some(format!("a{i}"));
+                graph.add_wire(input, y, &Elbow::HorzS);
+            }
+            // Already taken by a node, so the first partial result is named after it
+            let clash = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 8, y: 0 });
+            graph.node_mut(clash).name = Some("y_xor1".to_string());
+            graph.add_wire(y, clash, &Elbow::HorzS);
+
+            let blif = export(&graph, "parity").unwrap();
+            assert!(blif.lines().count() < 100, "{blif}");
+            assert!(blif.contains(".names a0 a1 y_xor1_1\n"));
+            assert!(blif.contains(".names y_xor18 a19 y\n"));
+            let reimported = import(&blif).unwrap();
+            assert_eq!(
+                check_equivalence(&graph, &reimported),
+                Ok(Equivalence::Equivalent)
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_long_chain() {
+        let mut blif = String::from(".inputs s0\n.outputs s50000\n");
+        for i in 1..=50_000 {
+            writeln!(blif, ".names s{} s{i}\n1 1", i - 1).unwrap();
+        }
+        let graph = import(&blif).unwrap();
+        assert_eq!(graph.nodes().count(), 50_001);
+    }
+}
@@ -0,0 +1,152 @@
+//! Graphviz, for looking at the topology of large designs outside the editor.
+
+use std::fmt::Write;
+
+use crate::{
+    formats::netlist::identifier,
+    graph::{node::NodeId, Graph},
+};
+
+fn quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Union-find lookup, halving the path as it goes.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Which weakly-connected component each node is in, numbered in order of their first node.
+fn connected_components(graph: &Graph) -> Vec<usize> {
+    let mut parent: Vec<usize> = graph.nodes().map(|(id, _)| id.0).collect();
+    for (_, wire) in graph.wires() {
+        let (a, b) = (
+            root(&mut parent, wire.input.0),
+            root(&mut parent, wire.output.0),
+        );
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut numbers: Vec<Option<usize>> = vec![None; parent.len()];
+    let mut next = 0;
+    (0..parent.len())
+        .map(|i| {
+            let r = root(&mut parent, i);
+            *numbers[r].get_or_insert_with(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect()
+}
+
+fn write_node(out: &mut String, graph: &Graph, id: NodeId, indent: &str) {
+    let node = graph.node(id);
+    let mut label = String::new();
+    if let Some(name) = &node.name {
+        label.push_str(name);
+        label.push('\n');
+    }
+    label.push_str(&format!("{}\n{}", node.gate, node.coords));
+    let label = quoted(&label).replace('\n', "\\n");
+    writeln!(out, "{indent}{id} [label={label}];").unwrap();
+}
+
+/// One vertex per node and one edge per wire, with wires in combinational loops in red.
+///
+/// With `clustered`, each separate circuit is boxed and labelled with its named outputs.
+pub fn export(graph: &Graph, name: &str, clustered: bool) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph {} {{", identifier(name)).unwrap();
+
+    if clustered {
+        let components = connected_components(graph);
+        let num_components = components.iter().max().map_or(0, |max| max + 1);
+        let output_ports = graph.output_ports();
+        for cluster in 0..num_components {
+            let members: Vec<NodeId> = graph
+                .nodes()
+                .map(|(id, _)| id)
+                .filter(|id| components[id.0] == cluster)
+                .collect();
+            let outputs: Vec<&str> = members
+                .iter()
+                .filter(|id| output_ports.contains(id))
+                .filter_map(|&id| graph.node(id).name.as_deref())
+                .collect();
+
+            writeln!(out, "    subgraph cluster_{cluster} {{").unwrap();
+            if !outputs.is_empty() {
+                writeln!(out, "        label={};", quoted(&outputs.join(", "))).unwrap();
+            }
+            for id in members {
+                write_node(&mut out, graph, id, "        ");
+            }
+            writeln!(out, "    }}").unwrap();
+        }
+    } else {
+        for (id, _) in graph.nodes() {
+            write_node(&mut out, graph, id, "    ");
+        }
+    }
+
+    let loop_wires: Vec<_> = graph
+        .find_combinational_loops()
+        .into_iter()
+        .flat_map(|combinational_loop| combinational_loop.wires)
+        .collect();
+    for (id, wire) in graph.wires() {
+        let style = match loop_wires.contains(&id) {
+            true => " [color=red]",
+            false => "",
+        };
+        writeln!(out, "    {} -> {}{style};", wire.input, wire.output).unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::save;
+
+    #[test]
+    fn test_golden_half_adder() {
+        let graph = save::load(include_str!("golden/half_adder.ea")).unwrap();
+        assert_eq!(
+            export(&graph, "half_adder", false),
+            include_str!("golden/half_adder.dot")
+        );
+    }
+
+    #[test]
+    fn test_clusters() {
+        let graph = save::load(
+            "electron-architect-rust 1
+node 0 0 buffer a
+node 2 0 buffer \"y\"
+node 0 4 always
+node 2 4 nor
+node 4 4 nor
+wire 0 1 horz_s
+wire 2 3 horz_s
+wire 3 4 horz_s
+wire 4 3 horz_s",
+        )
+        .unwrap();
+        assert_eq!(connected_components(&graph), vec![0, 0, 1, 1, 1]);
+
+        let dot = export(&graph, "two circuits", true);
+        assert!(dot.starts_with("digraph two_circuits {\n    subgraph cluster_0 {\n"));
+        assert!(dot.contains("        label=\"\\\"y\\\"\";\n"));
+        assert!(dot.contains("    subgraph cluster_1 {\n        n2 "));
+        assert!(dot.contains("    n3 -> n4 [color=red];\n"));
+        assert!(dot.contains("    n0 -> n1;\n"));
+    }
+}
@@ -0,0 +1,16 @@
+module half_adder (
+    input a,
+    input b,
+    output sum,
+    output and_6
+);
+    wire carry;
+    wire n4_2_4;
+    wire n5_6_4;
+
+    xor (sum, a, b);
+    and (carry, a, b);
+    assign n4_2_4 = 1'b1;
+    nand (n5_6_4, n4_2_4, carry);
+    buf (and_6, n5_6_4);
+endmodule
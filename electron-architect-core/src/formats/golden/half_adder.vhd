@@ -0,0 +1,23 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity half_adder is
+    port (
+        a : in std_logic;
+        b : in std_logic;
+        sum : out std_logic;
+        and_6 : out std_logic
+    );
+end entity half_adder;
+
+architecture structural of half_adder is
+    signal carry : std_logic;
+    signal n4_2_4 : std_logic;
+    signal n5_6_4 : std_logic;
+begin
+    sum <= a xor b;
+    carry <= a and b;
+    n4_2_4 <= '1';
+    n5_6_4 <= not (n4_2_4 and carry);
+    and_6 <= n5_6_4;
+end architecture structural;
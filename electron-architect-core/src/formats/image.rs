@@ -0,0 +1,460 @@
+//! Pictures of the canvas for documentation: SVG, and PNG drawn in software.
+
+use std::fmt::Write;
+
+use crate::{
+    coords::Coords,
+    formats::ExportOptions,
+    graph::{
+        node::{Node, NodeId},
+        wire::WireId,
+        Graph,
+    },
+};
+
+mod font;
+mod png;
+
+/// Empty grid cells left around the drawing, with extra room on the right for names.
+const MARGIN: i32 = 1;
+const NAME_MARGIN: i32 = 4;
+/// Largest width or height of a PNG, in pixels.
+const MAX_IMAGE_SIZE: i32 = 16384;
+
+/// Shades of grey, as on the canvas.
+const BACKGROUND: u8 = 0x00;
+const GATE_COLOR: u8 = 0x82;
+const NAME_COLOR: u8 = 0xc8;
+
+fn svg_color(shade: u8) -> String {
+    format!("#{shade:02x}{shade:02x}{shade:02x}")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The nodes and wires to draw, and the grid cells they fit in.
+struct Frame {
+    nodes: Vec<NodeId>,
+    wires: Vec<WireId>,
+    min: Coords,
+    max: Coords,
+}
+
+impl Frame {
+    fn new(graph: &Graph, region: Option<(Coords, Coords)>) -> Result<Self, String> {
+        let inside = |node: &Node| match region {
+            Some((a, b)) => {
+                (a.x.min(b.x)..=a.x.max(b.x)).contains(&node.coords.x)
+                    && (a.y.min(b.y)..=a.y.max(b.y)).contains(&node.coords.y)
+            }
+            None => true,
+        };
+        let nodes: Vec<NodeId> = graph
+            .nodes()
+            .filter(|(_, node)| inside(node))
+            .map(|(id, _)| id)
+            .collect();
+        let wires: Vec<WireId> = graph
+            .wires()
+            .filter(|(_, wire)| nodes.contains(&wire.input) && nodes.contains(&wire.output))
+            .map(|(id, _)| id)
+            .collect();
+
+        // Wire joints never leave the box around their ends and the points they're routed via
+        let coords: Vec<Coords> = nodes
+            .iter()
+            .map(|&id| graph.node(id).coords)
+            .chain(
+                wires
+                    .iter()
+                    .flat_map(|&id| graph.wire(id).via.iter().copied()),
+            )
+            .collect();
+        let (Some(min_x), Some(max_x)) = (
+            coords.iter().map(|c| c.x).min(),
+            coords.iter().map(|c| c.x).max(),
+        ) else {
+            return Err("There's nothing to draw".to_string());
+        };
+        let min_y = coords.iter().map(|c| c.y).min().unwrap();
+        let max_y = coords.iter().map(|c| c.y).max().unwrap();
+
+        Ok(Self {
+            nodes,
+            wires,
+            min: Coords {
+                x: min_x - MARGIN,
+                y: min_y - MARGIN,
+            },
+            max: Coords {
+                x: max_x + NAME_MARGIN,
+                y: max_y + MARGIN,
+            },
+        })
+    }
+
+    /// Size in pixels at the canvas' own scale.
+    fn size(&self) -> (f32, f32) {
+        (
+            (self.max.x - self.min.x + 1) as f32 * Coords::GRID_SIZE,
+            (self.max.y - self.min.y + 1) as f32 * Coords::GRID_SIZE,
+        )
+    }
+
+    /// Center of a grid cell, relative to the top left of the frame.
+    fn position(&self, coords: &Coords) -> (f32, f32) {
+        (
+            (coords.x - self.min.x) as f32 * Coords::GRID_SIZE + Coords::GRID_RADIUS,
+            (coords.y - self.min.y) as f32 * Coords::GRID_SIZE + Coords::GRID_RADIUS,
+        )
+    }
+
+    /// Start, corners and end of each wire.
+    fn wire_points<'a>(&'a self, graph: &'a Graph) -> impl Iterator<Item = Vec<(f32, f32)>> + 'a {
+        self.wires.iter().map(move |&id| {
+            let wire = graph.wire(id);
+            let start = graph.node(wire.input).coords;
+            let end = graph.node(wire.output).coords;
+            wire.corners(&start, &end)
+                .iter()
+                .map(|corner| self.position(corner))
+                .collect()
+        })
+    }
+}
+
+/// Vector drawing with a glyph in each gate and names next to their nodes, as on the canvas.
+pub fn export_svg(graph: &Graph, options: &ExportOptions) -> Result<String, String> {
+    let frame = Frame::new(graph, options.region)?;
+    let (width, height) = frame.size();
+    let scale = options.scale.unwrap_or(1.0);
+    let (background, gate_color, name_color) = (
+        svg_color(BACKGROUND),
+        svg_color(GATE_COLOR),
+        svg_color(NAME_COLOR),
+    );
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {width} {height}\">",
+        width * scale,
+        height * scale
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{background}\"/>"
+    )
+    .unwrap();
+
+    writeln!(out, "<g stroke=\"{gate_color}\" fill=\"none\">").unwrap();
+    for points in frame.wire_points(graph) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        writeln!(out, "<polyline points=\"{}\"/>", points.join(" ")).unwrap();
+    }
+    writeln!(out, "</g>").unwrap();
+
+    writeln!(
+        out,
+        "<g font-family=\"monospace\" text-anchor=\"middle\" dominant-baseline=\"central\">"
+    )
+    .unwrap();
+    for &id in &frame.nodes {
+        let node = graph.node(id);
+        let (x, y) = frame.position(&node.coords);
+        writeln!(
+            out,
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"{gate_color}\"/>",
+            Coords::GRID_RADIUS
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "<text x=\"{x}\" y=\"{y}\" font-size=\"{}\" fill=\"{background}\">{}</text>",
+            Coords::GRID_RADIUS,
+            escape(node.gate.glyph())
+        )
+        .unwrap();
+        if let Some(name) = &node.name {
+            writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{name_color}\" text-anchor=\"start\">{}</text>",
+                x + Coords::GRID_RADIUS,
+                y - Coords::GRID_RADIUS,
+                Node::NAME_FONT_SIZE,
+                escape(name)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "</g>").unwrap();
+
+    writeln!(out, "</svg>").unwrap();
+    Ok(out)
+}
+
+/// Grey pixels that the canvas is drawn into, at `scale` pixels per canvas pixel.
+struct Raster {
+    width: usize,
+    height: usize,
+    scale: f32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize, scale: f32) -> Self {
+        Self {
+            width,
+            height,
+            scale,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    /// Shades each pixel whose center `inside` holds for, given in canvas pixels,
+    /// checking only those between the corners `min` and `max`.
+    fn fill(
+        &mut self,
+        (min_x, min_y): (f32, f32),
+        (max_x, max_y): (f32, f32),
+        shade: u8,
+        inside: impl Fn(f32, f32) -> bool,
+    ) {
+        let column = |x: f32| ((x * self.scale).max(0.0) as usize).min(self.width);
+        let row = |y: f32| ((y * self.scale).max(0.0) as usize).min(self.height);
+        let (columns, rows) = (
+            column(min_x)..column(max_x).saturating_add(1).min(self.width),
+            row(min_y)..row(max_y).saturating_add(1).min(self.height),
+        );
+        for py in rows {
+            for px in columns.clone() {
+                let x = (px as f32 + 0.5) / self.scale;
+                let y = (py as f32 + 0.5) / self.scale;
+                if inside(x, y) {
+                    self.pixels[py * self.width + px] = shade;
+                }
+            }
+        }
+    }
+
+    /// One canvas pixel wide, but never thinner than one image pixel.
+    fn line(&mut self, (ax, ay): (f32, f32), (bx, by): (f32, f32), shade: u8) {
+        let half_width = 0.5f32.max(0.5 / self.scale);
+        let (dx, dy) = (bx - ax, by - ay);
+        let length_squared = dx * dx + dy * dy;
+        self.fill(
+            (ax.min(bx) - half_width, ay.min(by) - half_width),
+            (ax.max(bx) + half_width, ay.max(by) + half_width),
+            shade,
+            |x, y| {
+                // Distance from the nearest point on the line
+                let t = match length_squared > 0.0 {
+                    true => (((x - ax) * dx + (y - ay) * dy) / length_squared).clamp(0.0, 1.0),
+                    false => 0.0,
+                };
+                let (nx, ny) = (ax + t * dx - x, ay + t * dy - y);
+                nx * nx + ny * ny <= half_width * half_width
+            },
+        );
+    }
+
+    fn circle(&mut self, (cx, cy): (f32, f32), radius: f32, shade: u8) {
+        self.fill(
+            (cx - radius, cy - radius),
+            (cx + radius, cy + radius),
+            shade,
+            |x, y| (x - cx).powi(2) + (y - cy).powi(2) <= radius * radius,
+        );
+    }
+
+    /// Glyphs are scaled up from the font's own size, as raylib does,
+    /// and never drawn smaller than it.
+    fn text_size(size: i32) -> (f32, f32) {
+        let size = size.max(font::HEIGHT as i32);
+        (
+            size as f32 / font::HEIGHT as f32,
+            (size / font::HEIGHT as i32) as f32,
+        )
+    }
+
+    /// Width of `text` in canvas pixels.
+    fn measure_text(text: &str, size: i32) -> f32 {
+        let (scale, spacing) = Self::text_size(size);
+        let width: usize = text.chars().map(|c| font::glyph(c).0).sum();
+        width as f32 * scale + text.chars().count().saturating_sub(1) as f32 * spacing
+    }
+
+    /// `(x, y)` is the top left of the text.
+    fn text(&mut self, text: &str, (mut x, y): (f32, f32), size: i32, shade: u8) {
+        let (scale, spacing) = Self::text_size(size);
+        for c in text.chars() {
+            let (width, rows) = font::glyph(c);
+            self.fill(
+                (x, y),
+                (x + *width as f32 * scale, y + font::HEIGHT as f32 * scale),
+                shade,
+                |px, py| {
+                    let column = ((px - x) / scale).floor();
+                    let row = ((py - y) / scale).floor();
+                    (0.0..*width as f32).contains(&column)
+                        && (0.0..font::HEIGHT as f32).contains(&row)
+                        && rows[row as usize] & (1 << column as u32) != 0
+                },
+            );
+            x += *width as f32 * scale + spacing;
+        }
+    }
+}
+
+/// Drawn in software like the editor draws the canvas, so it needs no window.
+pub fn export_png(graph: &Graph, options: &ExportOptions) -> Result<Vec<u8>, String> {
+    let frame = Frame::new(graph, options.region)?;
+    let scale = options.scale.unwrap_or(1.0);
+    let (width, height) = frame.size();
+    let (width, height) = (
+        (width * scale).round() as i32,
+        (height * scale).round() as i32,
+    );
+    if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return Err(format!(
+            "A {width}x{height} image is too big, the limit is {MAX_IMAGE_SIZE} pixels a side"
+        ));
+    }
+    if width < 1 || height < 1 {
+        return Err(format!(
+            "A {width}x{height} image is too small, try a bigger scale"
+        ));
+    }
+
+    let mut raster = Raster::new(width as usize, height as usize, scale);
+    for points in frame.wire_points(graph) {
+        for segment in points.windows(2) {
+            raster.line(segment[0], segment[1], GATE_COLOR);
+        }
+    }
+    let glyph_size = Coords::GRID_RADIUS as i32;
+    for &id in &frame.nodes {
+        let node = graph.node(id);
+        let (x, y) = frame.position(&node.coords);
+        raster.circle((x, y), Coords::GRID_RADIUS, GATE_COLOR);
+        let glyph = node.gate.glyph();
+        raster.text(
+            glyph,
+            (
+                (x - Raster::measure_text(glyph, glyph_size) / 2.0).round(),
+                y - (glyph_size / 2) as f32,
+            ),
+            glyph_size,
+            BACKGROUND,
+        );
+        if let Some(name) = &node.name {
+            raster.text(
+                name,
+                (
+                    x + Coords::GRID_RADIUS,
+                    y - Coords::GRID_RADIUS - (Node::NAME_FONT_SIZE / 2) as f32,
+                ),
+                Node::NAME_FONT_SIZE,
+                NAME_COLOR,
+            );
+        }
+    }
+    Ok(png::encode(width as u32, height as u32, &raster.pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::save;
+
+    fn half_adder() -> Graph {
+        save::load(include_str!("golden/half_adder.ea")).unwrap()
+    }
+
+    #[test]
+    fn test_frame() {
+        let graph = half_adder();
+        let frame = Frame::new(&graph, None).unwrap();
+        assert_eq!(frame.nodes.len(), 7);
+        assert_eq!(frame.wires.len(), 7);
+        assert_eq!(frame.min, Coords { x: -1, y: -1 });
+        assert_eq!(frame.max, Coords { x: 12, y: 5 });
+        assert_eq!(frame.position(&Coords { x: 0, y: 0 }), (24.0, 24.0));
+    }
+
+    #[test]
+    fn test_region() {
+        let graph = half_adder();
+        let region = Some((Coords { x: 4, y: 0 }, Coords { x: 0, y: 2 }));
+        let frame = Frame::new(&graph, region).unwrap();
+        assert_eq!(
+            frame.nodes,
+            vec![NodeId(0), NodeId(1), NodeId(2), NodeId(3)]
+        );
+        assert_eq!(frame.wires.len(), 4);
+
+        let empty = Some((Coords { x: 20, y: 20 }, Coords { x: 30, y: 30 }));
+        assert!(Frame::new(&graph, empty).is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let options = ExportOptions {
+            scale: Some(1.5),
+            ..Default::default()
+        };
+        let svg = export_svg(&half_adder(), &options).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"336\" height=\"168\" viewBox=\"0 0 224 112\">"));
+        assert!(svg.contains("<polyline points=\"24,24 88,24\"/>"));
+        assert!(svg.contains(">&amp;</text>"));
+        assert!(svg.contains(">carry</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_raster() {
+        let mut raster = Raster::new(40, 20, 2.0);
+        raster.line((1.0, 1.0), (9.0, 1.0), GATE_COLOR);
+        raster.circle((15.0, 5.0), 2.0, GATE_COLOR);
+        raster.text("|", (0.0, 0.0), 8, NAME_COLOR);
+        let pixel = |x: usize, y: usize| raster.pixels[y * 40 + x];
+        // Lines are as thick as a canvas pixel
+        assert_eq!(pixel(10, 1), GATE_COLOR);
+        assert_eq!(pixel(10, 3), BACKGROUND);
+        assert_eq!(pixel(30, 10), GATE_COLOR);
+        assert_eq!(pixel(26, 6), BACKGROUND);
+        // Small text is drawn at the font's own size
+        assert_eq!(pixel(0, 17), NAME_COLOR);
+        assert_eq!(pixel(0, 19), BACKGROUND);
+        assert_eq!(Raster::measure_text("&|", 8), 8.0);
+    }
+
+    #[test]
+    fn test_png() {
+        let graph = half_adder();
+        let png = export_png(&graph, &ExportOptions::default()).unwrap();
+        assert_eq!(png[16..24], [0, 0, 0, 224, 0, 0, 0, 112]);
+        let png = export_png(
+            &graph,
+            &ExportOptions {
+                scale: Some(0.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(png[16..24], [0, 0, 0, 112, 0, 0, 0, 56]);
+
+        let scaled = |scale| ExportOptions {
+            scale: Some(scale),
+            ..Default::default()
+        };
+        assert!(export_png(&graph, &scaled(0.001)).is_err());
+        assert!(export_png(&graph, &scaled(100.0)).is_err());
+    }
+}
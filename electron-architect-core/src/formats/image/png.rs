@@ -0,0 +1,295 @@
+//! Greyscale PNG encoding. Drawings are mostly runs of one shade, so the pixels are
+//! compressed with fixed Huffman codes and run lengths, without searching for repeats.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Deflate compression with a 32KiB window, no dictionary and the fastest level.
+const ZLIB_HEADER: [u8; 2] = [0x78, 0x01];
+
+/// Shortest and longest run that deflate can repeat.
+const MIN_RUN: usize = 3;
+const MAX_RUN: usize = 258;
+
+/// Shortest run for each length code from 257, and how many extra bits follow it.
+const LENGTH_BASES: [(usize, u32); 29] = [
+    (3, 0),
+    (4, 0),
+    (5, 0),
+    (6, 0),
+    (7, 0),
+    (8, 0),
+    (9, 0),
+    (10, 0),
+    (11, 1),
+    (13, 1),
+    (15, 1),
+    (17, 1),
+    (19, 2),
+    (23, 2),
+    (27, 2),
+    (31, 2),
+    (35, 3),
+    (43, 3),
+    (51, 3),
+    (59, 3),
+    (67, 4),
+    (83, 4),
+    (99, 4),
+    (115, 4),
+    (131, 5),
+    (163, 5),
+    (195, 5),
+    (227, 5),
+    (258, 0),
+];
+
+/// Packs bits into bytes, least significant first, as deflate reads them.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, bits: u32, len: u32) {
+        self.buffer |= (bits as u64) << self.len;
+        self.len += len;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Huffman codes are read most significant bit first.
+    fn write_code(&mut self, code: u32, len: u32) {
+        self.write(code.reverse_bits() >> (32 - len), len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Writes a literal byte, end of block (256) or length code (257 and up)
+/// in deflate's fixed Huffman code.
+fn write_symbol(out: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => out.write_code(0x30 + symbol, 8),
+        144..=255 => out.write_code(0x190 + symbol - 144, 9),
+        256..=279 => out.write_code(symbol - 256, 7),
+        _ => out.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+/// Repeats the previous byte `run` more times.
+fn write_run(out: &mut BitWriter, run: usize) {
+    let code = LENGTH_BASES
+        .iter()
+        .rposition(|&(base, _)| base <= run)
+        .unwrap(); // The first base is the shortest run
+    let (base, extra_bits) = LENGTH_BASES[code];
+    write_symbol(out, 257 + code as u32);
+    out.write((run - base) as u32, extra_bits);
+    // Distance code 0 is one byte back, and has no extra bits
+    out.write_code(0, 5);
+}
+
+/// A single fixed Huffman block of `data`.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::default();
+    // Last block, fixed codes
+    out.write(0b011, 3);
+    let mut i = 0;
+    while i < data.len() {
+        let run = match i {
+            0 => 0,
+            _ => data[i..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|&&byte| byte == data[i - 1])
+                .count(),
+        };
+        match run >= MIN_RUN {
+            true => {
+                write_run(&mut out, run);
+                i += run;
+            }
+            false => {
+                write_symbol(&mut out, data[i] as u32);
+                i += 1;
+            }
+        }
+    }
+    write_symbol(&mut out, 256);
+    out.finish()
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    // The most bytes that can be summed before `b` could overflow
+    const CHUNK: usize = 5552;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(CHUNK) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let table: Vec<u32> = (0..256)
+        .map(|n| {
+            (0..8).fold(n, |c, _| match c & 1 {
+                1 => 0xedb88320 ^ (c >> 1),
+                _ => c >> 1,
+            })
+        })
+        .collect();
+    !data.iter().fold(!0u32, |crc, &byte| {
+        table[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// PNG file of 8-bit grey `pixels`, row by row from the top.
+pub fn encode(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    // Each row starts with the filter it uses, which is none
+    let rows: Vec<u8> = pixels
+        .chunks(width as usize)
+        .flat_map(|row| [0].iter().chain(row))
+        .copied()
+        .collect();
+    let mut compressed = ZLIB_HEADER.to_vec();
+    compressed.extend(deflate(&rows));
+    compressed.extend(adler32(&rows).to_be_bytes());
+
+    let mut header = Vec::new();
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits deep, greyscale, deflated, filtered by row, not interlaced
+    header.extend([8, 0, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &header);
+    write_chunk(&mut out, b"IDAT", &compressed);
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BitReader<'a> {
+        data: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bit(&mut self) -> u32 {
+            let value = (self.data[self.position / 8] >> (self.position % 8)) & 1;
+            self.position += 1;
+            value as u32
+        }
+
+        fn bits(&mut self, len: u32) -> u32 {
+            (0..len).map(|i| self.bit() << i).sum()
+        }
+    }
+
+    /// Just enough of inflate to read back a fixed Huffman block of byte runs.
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut reader = BitReader { data, position: 0 };
+        assert_eq!(reader.bits(3), 0b011);
+
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            // Fixed codes are told apart by their first 7 to 9 bits
+            let mut code = 0;
+            let mut len = 0;
+            let symbol = loop {
+                code = (code << 1) | reader.bit();
+                len += 1;
+                match (len, code) {
+                    (7, 0..=23) => break code + 256,
+                    (8, 0x30..=0xbf) => break code - 0x30,
+                    (8, 0xc0..=0xc7) => break code - 0xc0 + 280,
+                    (9, 0x190..=0x1ff) => break code - 0x190 + 144,
+                    _ => assert!(len < 9),
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let (base, extra_bits) = LENGTH_BASES[symbol as usize - 257];
+                    let run = base + reader.bits(extra_bits) as usize;
+                    assert_eq!(reader.bits(5), 0, "only runs of the last byte");
+                    let last = *out.last().unwrap();
+                    out.extend(std::iter::repeat_n(last, run));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deflate() {
+        let mut data = vec![0x82, 7, 7, 200, 200, 200, 200];
+        data.extend([0; 1000]);
+        data.extend([9; 12]);
+        data.extend((0..=255).collect::<Vec<u8>>());
+        let compressed = deflate(&data);
+        assert!(compressed.len() < 400);
+        assert_eq!(inflate(&compressed), data);
+        assert_eq!(inflate(&deflate(&[])), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(adler32(&[0xff; 100_000]), {
+            let (a, b) = (0..100_000u64).fold((1u64, 0u64), |(a, b), _| {
+                let a = (a + 0xff) % 65521;
+                (a, (b + a) % 65521)
+            });
+            ((b << 16) | a) as u32
+        });
+    }
+
+    #[test]
+    fn test_encode() {
+        let png = encode(3, 2, &[0, 0x82, 0xc8, 0xc8, 0x82, 0]);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+        let idat = &png[33..];
+        assert_eq!(&idat[4..8], b"IDAT");
+        let len = u32::from_be_bytes(idat[..4].try_into().unwrap()) as usize;
+        let zlib = &idat[8..8 + len];
+        assert_eq!(zlib[..2], ZLIB_HEADER);
+        assert_eq!(
+            inflate(&zlib[2..len - 4]),
+            [0, 0, 0x82, 0xc8, 0, 0xc8, 0x82, 0]
+        );
+    }
+}
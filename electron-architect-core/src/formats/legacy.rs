@@ -0,0 +1,231 @@
+//! Save files from the original C++ Electron Architect.
+//!
+//! ```text
+//! version <n>
+//! nodes <count>
+//! <gate> <x> <y>
+//! wires <count>
+//! <input node> <output node> <elbow>
+//! ```
+//!
+//! Gates are the original's one-character identifiers, positions are world pixels on its
+//! 8 pixel grid, and elbows are numbered horizontal, diagonal A, vertical, diagonal B.
+//! The original had analogue-ish parts with no equivalent here, which are approximated
+//! and reported rather than failing the load.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    coords::Coords,
+    graph::{
+        elbow::Elbow,
+        gate::{Gate, Gate0, Gate1, GateN},
+        node::NodeId,
+        Graph,
+    },
+};
+
+/// The original's grid spacing, in pixels.
+const GRID: f32 = 8.0;
+
+/// Whether `s` looks like one of the original's save files.
+pub fn is_legacy(s: &str) -> bool {
+    s.lines()
+        .next()
+        .is_some_and(|line| line.split_whitespace().next() == Some("version"))
+}
+
+/// The closest gate, and what was lost if it's not exact.
+fn map_gate(id: &str) -> Option<(Gate, Option<&'static str>)> {
+    Some(match id {
+        "|" => (Gate::GN(GateN::Or), None),
+        "&" => (Gate::GN(GateN::And), None),
+        "!" => (Gate::GN(GateN::Nor), None),
+        "^" => (Gate::GN(GateN::Xor), None),
+        "#" => (Gate::G0(Gate0::Always), Some("battery")),
+        "~" => (Gate::G1(Gate1::Buffer), Some("resistor")),
+        "=" => (Gate::G1(Gate1::Buffer), Some("capacitor")),
+        "@" => (Gate::G1(Gate1::Buffer), Some("LED")),
+        ";" => (Gate::G1(Gate1::Buffer), Some("delay")),
+        _ => return None,
+    })
+}
+
+fn map_elbow(id: &str) -> Option<Elbow> {
+    match id {
+        "0" => Some(Elbow::HorzS),
+        "1" => Some(Elbow::DiagS),
+        "2" => Some(Elbow::VertS),
+        "3" => Some(Elbow::DiagE),
+        _ => None,
+    }
+}
+
+/// Reads `<keyword> <count>`.
+fn section<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    keyword: &str,
+) -> Result<usize, String> {
+    let (i, line) = lines
+        .next()
+        .ok_or_else(|| format!("Expected `{keyword} <count>`"))?;
+    match line.split_whitespace().collect::<Vec<&str>>()[..] {
+        [word, count] if word == keyword => count
+            .parse()
+            .map_err(|_| format!("Line {}: Expected a count after `{keyword}`", i + 1)),
+        _ => Err(format!("Line {}: Expected `{keyword} <count>`", i + 1)),
+    }
+}
+
+/// Loads the graph along with a report of everything that didn't map exactly.
+pub fn load(s: &str) -> Result<(Graph, Vec<String>), String> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if is_legacy(line) => {}
+        _ => return Err("Not an original Electron Architect save file".to_string()),
+    }
+
+    let mut graph = Graph::new();
+    let mut report = Vec::new();
+    // Counts of each approximated part, reported once per kind
+    let mut approximated: BTreeMap<&str, (Gate, usize)> = BTreeMap::new();
+
+    let num_nodes = section(&mut lines, "nodes")?;
+    let mut ids: Vec<Option<NodeId>> = Vec::with_capacity(num_nodes);
+    for _ in 0..num_nodes {
+        let (i, line) = lines.next().ok_or("File ends partway through the nodes")?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [gate, x, y] = fields[..] else {
+            return Err(format!("Line {}: Expected `<gate> <x> <y>`", i + 1));
+        };
+        let (Ok(x), Ok(y)) = (x.parse::<f32>(), y.parse::<f32>()) else {
+            return Err(format!("Line {}: Expected a position", i + 1));
+        };
+        let Some((mapped, lost)) = map_gate(gate) else {
+            report.push(format!("Line {}: Left out unknown gate `{gate}`", i + 1));
+            ids.push(None);
+            continue;
+        };
+        if let Some(lost) = lost {
+            approximated.entry(lost).or_insert((mapped, 0)).1 += 1;
+        }
+
+        let coords = Coords {
+            x: (x / GRID).round() as i32,
+            y: (y / GRID).round() as i32,
+        };
+        if graph.find_node_at_coords(&coords).is_some() {
+            report.push(format!(
+                "Line {}: Left out a node overlapping another at {coords}",
+                i + 1
+            ));
+            ids.push(None);
+            continue;
+        }
+        ids.push(Some(graph.add_node(&mapped, &coords)));
+    }
+
+    let num_wires = section(&mut lines, "wires")?;
+    let mut dropped_wires = 0;
+    for _ in 0..num_wires {
+        let (i, line) = lines.next().ok_or("File ends partway through the wires")?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [input, output, elbow] = fields[..] else {
+            return Err(format!(
+                "Line {}: Expected `<input> <output> <elbow>`",
+                i + 1
+            ));
+        };
+        let node = |field: &str| -> Result<Option<NodeId>, String> {
+            field
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| ids.get(index).copied())
+                .ok_or_else(|| format!("Line {}: Wire refers to a node that doesn't exist", i + 1))
+        };
+        let elbow = map_elbow(elbow).unwrap_or_else(|| {
+            report.push(format!(
+                "Line {}: Unknown elbow `{elbow}` drawn horizontally",
+                i + 1
+            ));
+            Elbow::HorzS
+        });
+        match (node(input)?, node(output)?) {
+            (Some(input), Some(output)) => match graph.check_inputs(output) {
+                Ok(()) => {
+                    graph.add_wire(input, output, &elbow);
+                }
+                Err(e) => report.push(format!("Line {}: {e}, so left out a wire into it", i + 1)),
+            },
+            _ => dropped_wires += 1,
+        }
+    }
+
+    for (part, (gate, count)) in approximated {
+        report.push(format!("{count} {part}(s) loaded as {gate}"));
+    }
+    if dropped_wires > 0 {
+        report.push(format!(
+            "Left out {dropped_wires} wire(s) connected to nodes that were left out"
+        ));
+    }
+    Ok((graph, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_FILE: &str = "version 2
+nodes 5
+& 0 0
+| 32 8
+@ 64 8
+; 64 40
+? 96 40
+wires 4
+0 1 3
+1 2 0
+2 3 2
+3 4 1
+";
+
+    #[test]
+    fn test_load() {
+        let (graph, report) = load(TEST_FILE).unwrap();
+        assert_eq!(graph.nodes().count(), 4);
+        assert_eq!(graph.node(NodeId(1)).gate, Gate::GN(GateN::Or));
+        assert_eq!(graph.node(NodeId(1)).coords, Coords { x: 4, y: 1 });
+        assert_eq!(graph.node(NodeId(3)).gate, Gate::G1(Gate1::Buffer));
+
+        let elbows: Vec<Elbow> = graph.wires().map(|(_, wire)| wire.elbow).collect();
+        assert_eq!(elbows, vec![Elbow::DiagE, Elbow::HorzS, Elbow::VertS]);
+
+        assert_eq!(
+            report,
+            vec![
+                "Line 7: Left out unknown gate `?`",
+                "1 LED(s) loaded as 1-Gate buffer",
+                "1 delay(s) loaded as 1-Gate buffer",
+                "Left out 1 wire(s) connected to nodes that were left out",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert!(load("nodes 0\nwires 0").is_err());
+        assert!(load("version 2\nnodes 2\n& 0 0\nwires 0").is_err());
+        assert!(load("version 2\nnodes 1\n& 0\nwires 0").is_err());
+        assert!(load("version 2\nnodes 1\n& 0 0\nwires 1\n0 1 0").is_err());
+    }
+
+    #[test]
+    fn test_is_legacy() {
+        assert!(is_legacy(TEST_FILE));
+        assert!(!is_legacy("electron-architect-rust 1\n"));
+    }
+}
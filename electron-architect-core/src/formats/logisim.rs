@@ -0,0 +1,399 @@
+//! Logisim `.circ` files.
+//!
+//! Only single-bit pins, constants and the basic gates map onto nodes.
+//! Logisim connects components through wire segments meeting at their ports,
+//! so each net of segments becomes wires from whichever component drives it.
+
+use std::collections::HashMap;
+
+use crate::{
+    coords::Coords,
+    formats::xml::{self, Element},
+    graph::{
+        gate::{Gate, Gate0, Gate1, GateN},
+        node::{Node, NodeId},
+        synth::choose_elbow,
+        Graph,
+    },
+};
+
+/// Logisim's grid spacing, in pixels.
+const GRID: i32 = 10;
+
+type Point = (i32, i32);
+
+fn parse_point(s: &str) -> Option<Point> {
+    let (x, y) = s.strip_prefix('(')?.strip_suffix(')')?.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// The component's settings, from its `<a name=".." val=".."/>` children.
+fn attributes(comp: &Element) -> HashMap<&str, &str> {
+    comp.children_named("a")
+        .filter_map(|a| Some((a.attribute("name")?, a.attribute("val")?)))
+        .collect()
+}
+
+/// Rotates an offset given for an east-facing component.
+fn face(facing: &str, (x, y): Point) -> Point {
+    match facing {
+        "west" => (-x, y),
+        "north" => (y, -x),
+        "south" => (y, x),
+        _ => (x, y),
+    }
+}
+
+/// Input offsets of the n-input gates, matching Logisim's `AbstractGate`.
+fn gate_input_offsets(inputs: i32, size: i32, axis_length: i32) -> Vec<Point> {
+    let (skip_start, skip_dist, skip_lower_even) = match (inputs, size) {
+        (..=3, ..=39) => (-5, 10, 10),
+        (..=2, _) | (3, ..=59) => (-10, 20, 20),
+        (3, _) => (-15, 30, 30),
+        (4, 60..) => (-5, 20, 0),
+        _ => (-5, 10, 10),
+    };
+    (0..inputs)
+        .map(|i| {
+            let dy = match inputs % 2 {
+                1 => skip_start * (inputs - 1) + skip_dist * i,
+                _ if i >= inputs / 2 => skip_start * inputs + skip_dist * i + skip_lower_even,
+                _ => skip_start * inputs + skip_dist * i,
+            };
+            (-axis_length, dy)
+        })
+        .collect()
+}
+
+/// A component that maps onto a node.
+struct Part {
+    gate: Gate,
+    name: Option<String>,
+    loc: Point,
+    /// Whether anything can be wired from it, which isn't the case for output pins.
+    drives: bool,
+    inputs: Vec<Point>,
+}
+
+/// Maps a component onto a node, or says why it can't be.
+fn part(comp: &Element) -> Result<Part, String> {
+    let name = comp.attribute("name").unwrap_or("component");
+    if comp.attribute("lib").is_none() {
+        return Err(format!("subcircuit `{name}`"));
+    }
+    let attrs = attributes(comp);
+    if attrs.get("width").is_some_and(|&width| width != "1") {
+        return Err(format!("multi-bit {name}"));
+    }
+    if attrs.keys().any(|key| key.starts_with("negate")) {
+        return Err(format!("{name} with negated inputs"));
+    }
+    let loc = comp
+        .attribute("loc")
+        .and_then(parse_point)
+        .ok_or_else(|| format!("{name} without a location"))?;
+    let facing = attrs.get("facing").copied().unwrap_or("east");
+    let number = |key: &str, default: i32| -> i32 {
+        attrs
+            .get(key)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+
+    let (gate, drives, inputs) = match name {
+        "Pin" if attrs.get("output") == Some(&"true") => {
+            (Gate::G1(Gate1::Buffer), false, vec![(0, 0)])
+        }
+        "Pin" => (Gate::G1(Gate1::Buffer), true, Vec::new()),
+        "Constant" => {
+            let value = attrs.get("value").copied().unwrap_or("0x1");
+            let value = match value.strip_prefix("0x") {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None => value.parse(),
+            };
+            let gate = match value {
+                Ok(0) => Gate0::Never,
+                _ => Gate0::Always,
+            };
+            (Gate::G0(gate), true, Vec::new())
+        }
+        "Power" => (Gate::G0(Gate0::Always), true, Vec::new()),
+        "Ground" => (Gate::G0(Gate0::Never), true, Vec::new()),
+        "Buffer" => (Gate::G1(Gate1::Buffer), true, vec![(-20, 0)]),
+        // A single-input nor is our inverter
+        "NOT Gate" => {
+            let size = number("size", 30);
+            (Gate::GN(GateN::Nor), true, vec![(-size, 0)])
+        }
+        _ => {
+            let (gate, bonus_width, negated) = match name {
+                "AND Gate" => (GateN::And, 0, false),
+                "NAND Gate" => (GateN::Nand, 0, true),
+                "OR Gate" => (GateN::Or, 0, false),
+                "NOR Gate" => (GateN::Nor, 0, true),
+                "XOR Gate" => (GateN::Xor, 10, false),
+                "XNOR Gate" => (GateN::Xnor, 10, true),
+                _ => return Err(name.to_string()),
+            };
+            let size = number("size", 50);
+            let axis_length = size + bonus_width + if negated { 10 } else { 0 };
+            let offsets = gate_input_offsets(number("inputs", 5), size, axis_length);
+            (Gate::GN(gate), true, offsets)
+        }
+    };
+
+    let inputs = inputs
+        .into_iter()
+        .map(|offset| {
+            let (dx, dy) = face(facing, offset);
+            (loc.0 + dx, loc.1 + dy)
+        })
+        .collect();
+    Ok(Part {
+        gate,
+        name: attrs
+            .get("label")
+            .filter(|label| !label.trim().is_empty())
+            .map(|label| label.to_string()),
+        loc,
+        drives,
+        inputs,
+    })
+}
+
+/// Groups points joined by wire segments, including where one segment ends partway along another.
+struct Nets {
+    index: HashMap<Point, usize>,
+    parent: Vec<usize>,
+    segments: Vec<(Point, Point)>,
+}
+
+impl Nets {
+    fn point(&mut self, point: Point) -> usize {
+        let next = self.parent.len();
+        let i = *self.index.entry(point).or_insert(next);
+        if i == next {
+            self.parent.push(i);
+        }
+        i
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn join(&mut self, a: Point, b: Point) {
+        let (a, b) = (self.point(a), self.point(b));
+        let (a, b) = (self.root(a), self.root(b));
+        self.parent[a.max(b)] = a.min(b);
+    }
+
+    /// The net a component port is on, joining it to any segment it touches.
+    fn net(&mut self, point: Point) -> usize {
+        let touching: Vec<Point> = self
+            .segments
+            .iter()
+            .filter(|(a, b)| {
+                (a.0.min(b.0)..=a.0.max(b.0)).contains(&point.0)
+                    && (a.1.min(b.1)..=a.1.max(b.1)).contains(&point.1)
+            })
+            .map(|&(a, _)| a)
+            .collect();
+        for end in touching {
+            self.join(point, end);
+        }
+        let i = self.point(point);
+        self.root(i)
+    }
+}
+
+/// Reads the main circuit, or the first if none is marked as main.
+///
+/// Components that can't be imported are left out and described in the returned warnings.
+pub fn import(s: &str) -> Result<(Graph, Vec<String>), String> {
+    let project = xml::parse(s)?;
+    if project.name != "project" {
+        return Err("Not a Logisim project".to_string());
+    }
+    let main = project
+        .children_named("main")
+        .next()
+        .and_then(|main| main.attribute("name"));
+    let mut circuits = project.children_named("circuit");
+    let circuit = match main {
+        Some(main) => circuits.find(|circuit| circuit.attribute("name") == Some(main)),
+        None => circuits.next(),
+    }
+    .ok_or("The project has no circuits")?;
+
+    let mut warnings = Vec::new();
+    let mut nets = Nets {
+        index: HashMap::new(),
+        parent: Vec::new(),
+        segments: Vec::new(),
+    };
+    for wire in circuit.children_named("wire") {
+        let from = wire.attribute("from").and_then(parse_point);
+        let to = wire.attribute("to").and_then(parse_point);
+        let (Some(from), Some(to)) = (from, to) else {
+            return Err("Wire without both ends".to_string());
+        };
+        nets.segments.push((from, to));
+    }
+    for (from, to) in nets.segments.clone() {
+        nets.join(from, to);
+        for end in [from, to] {
+            nets.net(end);
+        }
+    }
+
+    let mut graph = Graph::new();
+    let mut parts: Vec<(NodeId, Part)> = Vec::new();
+    for comp in circuit.children_named("comp") {
+        // Labels have no effect on the circuit
+        if comp.attribute("name") == Some("Text") {
+            continue;
+        }
+        let part = match part(comp) {
+            Ok(part) => part,
+            Err(what) => {
+                let at = comp.attribute("loc").unwrap_or("unknown location");
+                warnings.push(format!("Skipped unsupported {what} at {at}"));
+                continue;
+            }
+        };
+        let coords = Coords {
+            x: (part.loc.0 as f32 / GRID as f32).round() as i32,
+            y: (part.loc.1 as f32 / GRID as f32).round() as i32,
+        };
+        if graph.find_node_at_coords(&coords).is_some() {
+            warnings.push(format!("Skipped component overlapping another at {coords}"));
+            continue;
+        }
+        let id = graph.add_node(&part.gate, &coords);
+        if let Some(label) = &part.name {
+            let name = label.split_whitespace().collect::<Vec<_>>().join("_");
+            if !Node::is_valid_name(label) {
+                warnings.push(format!(
+                    "Renamed `{label}` to `{name}`, as names are one word"
+                ));
+            }
+            graph.node_mut(id).name = Some(name);
+        }
+        parts.push((id, part));
+    }
+
+    let mut drivers: HashMap<usize, Option<NodeId>> = HashMap::new();
+    for (id, part) in &parts {
+        if part.drives {
+            let net = nets.net(part.loc);
+            drivers
+                .entry(net)
+                .and_modify(|driver| *driver = None)
+                .or_insert(Some(*id));
+        }
+    }
+    let mut conflicts: Vec<usize> = Vec::new();
+    for (id, part) in &parts {
+        for &input in &part.inputs {
+            let net = nets.net(input);
+            match drivers.get(&net) {
+                Some(Some(driver)) => {
+                    let start = graph.node(*driver).coords;
+                    let end = graph.node(*id).coords;
+                    graph.add_wire(*driver, *id, &choose_elbow(&start, &end));
+                }
+                Some(None) if !conflicts.contains(&net) => {
+                    conflicts.push(net);
+                    warnings.push(format!(
+                        "Left out a wire driven by more than one component, near ({}, {})",
+                        input.0, input.1
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok((graph, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::save;
+
+    const TEST_FILE: &str = include_str!("golden/nand.circ");
+    #[test]
+    fn test_gate_input_offsets() {
+        assert_eq!(gate_input_offsets(2, 50, 50), vec![(-50, -20), (-50, 20)]);
+        assert_eq!(
+            gate_input_offsets(5, 50, 50),
+            vec![(-50, -20), (-50, -10), (-50, 0), (-50, 10), (-50, 20)]
+        );
+        assert_eq!(
+            gate_input_offsets(3, 30, 30),
+            vec![(-30, -10), (-30, 0), (-30, 10)]
+        );
+    }
+
+    #[test]
+    fn test_import() {
+        let (graph, warnings) = import(TEST_FILE).unwrap();
+        assert_eq!(warnings, vec!["Skipped unsupported Clock at (40,200)"]);
+        assert_eq!(graph.nodes().count(), 6);
+
+        let find = |name: &str| graph.find_node_by_name(name).unwrap();
+        assert_eq!(graph.node(find("a")).coords, Coords { x: 10, y: 10 });
+        let table = graph
+            .truth_table(&[find("a"), find("b")], &[find("y"), find("z")])
+            .unwrap();
+        let rows: Vec<Vec<bool>> = table.rows.into_iter().map(|row| row.outputs).collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![true, false],
+                vec![true, false],
+                vec![true, true],
+                vec![false, true],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_label_with_spaces() {
+        let (graph, warnings) = import(
+            "<project><circuit name=\"main\">
+  <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\"><a name=\"label\" val=\" carry  out\"/></comp>
+  <comp lib=\"0\" loc=\"(100,200)\" name=\"Pin\"><a name=\"label\" val=\" \"/></comp>
+</circuit></project>",
+        )
+        .unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Renamed ` carry  out` to `carry_out`, as names are one word"]
+        );
+        let saved = save::save(&graph);
+        assert!(saved.contains(" carry_out\n"), "{saved}");
+        let loaded = save::load(&saved).unwrap();
+        assert!(loaded.find_node_by_name("carry_out").is_some());
+        assert_eq!(
+            loaded
+                .nodes()
+                .filter(|(_, node)| node.name.is_some())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(import("<circuit/>").is_err());
+        assert!(import("<project></project>").is_err());
+        assert!(import("<project><circuit><wire from=\"(0,0)\"/></circuit></project>").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::graph::{
+    gate::{Gate, Gate0, Gate1, GateN},
+    node::NodeId,
+    Graph,
+};
+
+/// What sets the value of a signal.
+pub enum Driver {
+    /// Module input port
+    Input,
+    Const(bool),
+    Buffer(NodeId),
+    Gate(GateN, Vec<NodeId>),
+}
+
+pub struct Signal {
+    pub name: String,
+    pub driver: Driver,
+}
+
+/// A graph flattened into named signals, shared by the HDL exporters.
+pub struct Netlist {
+    pub name: String,
+    /// One per node, indexed by [`NodeId`].
+    pub signals: Vec<Signal>,
+    pub inputs: Vec<NodeId>,
+    pub outputs: Vec<NodeId>,
+}
+
+/// Reduces `name` to letters, digits and single underscores, starting with a letter,
+/// which is a valid identifier in every HDL we export to.
+pub fn identifier(name: &str) -> String {
+    let mut id = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            id.push(ch);
+        } else if !id.is_empty() && !id.ends_with('_') {
+            id.push('_');
+        }
+    }
+    let id = id.trim_end_matches('_');
+    match id.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => id.to_string(),
+        Some(_) => format!("s{id}"),
+        None => "s".to_string(),
+    }
+}
+
+/// Name for an unnamed node, which stays the same as long as the node doesn't move
+/// and nothing is added before it.
+pub fn node_identifier(graph: &Graph, id: NodeId) -> String {
+    let coords = graph.node(id).coords;
+    let coord = |v: i32| match v < 0 {
+        true => format!("m{}", -v),
+        false => v.to_string(),
+    };
+    format!("n{}_{}_{}", id.0, coord(coords.x), coord(coords.y))
+}
+
+impl Netlist {
+    /// `reserved` are keywords of the target language, which are compared case-insensitively.
+    pub fn build(graph: &Graph, name: &str, reserved: &[&str]) -> Result<Self, String> {
+        let inputs = graph.input_ports();
+        let outputs = graph.output_ports();
+        let input_lists = graph.input_lists();
+
+        let mut taken: Vec<String> = reserved.iter().map(|word| word.to_lowercase()).collect();
+        let mut claim = |candidate: String, suffix: &str| {
+            // The suffixed name can itself be taken, by a node named after it
+            let name = [candidate.clone(), format!("{candidate}_{suffix}")]
+                .into_iter()
+                .chain((1..).map(|i| format!("{candidate}_{suffix}_{i}")))
+                .find(|name| !taken.contains(&name.to_lowercase()))
+                .unwrap(); // Only so many names are taken
+            taken.push(name.to_lowercase());
+            name
+        };
+
+        let module_name = claim(identifier(name), "top");
+        let mut signals = Vec::new();
+        for (id, node) in graph.nodes() {
+            let name = match &node.name {
+                Some(name) => identifier(name),
+                None => node_identifier(graph, id),
+            };
+            let name = claim(name, &id.0.to_string());
+            let ins = &input_lists[id.0];
+            let driver = match node.gate {
+                _ if inputs.contains(&id) => Driver::Input,
+                Gate::G0(Gate0::Random) => {
+                    return Err(format!(
+                        "{} is random and can't be exported",
+                        graph.node_label(id)
+                    ))
+                }
+                // Constants ignore anything wired into them
+                Gate::G0(_) => Driver::Const(node.gate.evaluate(&[], &mut || false)),
+                // Nothing wired in, so it can only ever be one value
+                gate if ins.is_empty() => Driver::Const(gate.evaluate(&[], &mut || false)),
+                Gate::G1(Gate1::Buffer) => Driver::Buffer(ins[0]),
+                Gate::GN(gate) => Driver::Gate(gate, ins.clone()),
+                Gate::Custom(_) => {
+                    return Err(format!(
+                        "{} is a custom `{}` gate and can't be exported",
+                        graph.node_label(id),
+                        node.gate.name()
+                    ))
+                }
+            };
+            signals.push(Signal { name, driver });
+        }
+
+        Ok(Self {
+            name: module_name,
+            signals,
+            inputs,
+            outputs,
+        })
+    }
+
+    pub fn signal_name(&self, id: NodeId) -> &str {
+        &self.signals[id.0].name
+    }
+
+    /// Signals that aren't ports.
+    pub fn internal_signals(&self) -> impl Iterator<Item = (NodeId, &Signal)> {
+        self.signals
+            .iter()
+            .enumerate()
+            .map(|(i, signal)| (NodeId(i), signal))
+            .filter(|(id, _)| !self.inputs.contains(id) && !self.outputs.contains(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Coords;
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("carry_out"), "carry_out");
+        assert_eq!(identifier("a--b"), "a_b");
+        assert_eq!(identifier("_x_"), "x");
+        assert_eq!(identifier("1st"), "s1st");
+        assert_eq!(identifier("(3, -4)"), "s3_4");
+        assert_eq!(identifier("!!"), "s");
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        let mut graph = Graph::new();
+        let buffer = Gate::G1(Gate1::Buffer);
+        for (y, name) in ["y_2", "y", "Y", "y_2_1"].into_iter().enumerate() {
+            let id = graph.add_node(&buffer, &Coords { x: 0, y: y as i32 });
+            graph.node_mut(id).name = Some(name.to_string());
+        }
+        // Named like the next node, which is unnamed
+        let id = graph.add_node(&buffer, &Coords { x: 0, y: 5 });
+        graph.node_mut(id).name = Some("n5_0_4".to_string());
+        graph.add_node(&buffer, &Coords { x: 0, y: 4 });
+
+        let netlist = Netlist::build(&graph, "y", &["wire"]).unwrap();
+        let names: Vec<&str> = (0..6).map(|i| netlist.signal_name(NodeId(i))).collect();
+        assert_eq!(
+            names,
+            ["y_2", "y_1", "Y_2_1", "y_2_1_3", "n5_0_4", "n5_0_4_5"]
+        );
+        assert_eq!(netlist.name, "y");
+    }
+}
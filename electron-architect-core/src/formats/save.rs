@@ -0,0 +1,174 @@
+//! The native save format.
+//!
+//! Plain text, one item per line:
+//!
+//! ```text
+//! electron-architect-rust 1
+//! node <x> <y> <gate> [name]
+//! wire <input node> <output node> <elbow> [<x>,<y>...]
+//! ```
+//!
+//! Wires refer to nodes by the order they appear in the file, starting from 0,
+//! and list any points they're routed via after their elbow.
+
+use std::{fs, str::SplitWhitespace};
+
+use crate::{
+    coords::Coords,
+    graph::{elbow::Elbow, gate::Gate, node::NodeId, Graph},
+};
+
+const HEADER: &str = "electron-architect-rust 1";
+
+pub fn save(graph: &Graph) -> String {
+    let mut out = format!("{HEADER}\n");
+    for (_, node) in graph.nodes() {
+        let line = format!(
+            "node {} {} {} {}",
+            node.coords.x,
+            node.coords.y,
+            node.gate.name(),
+            node.name.as_deref().unwrap_or(""),
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    for (_, wire) in graph.wires() {
+        out.push_str(&format!(
+            "wire {} {} {}",
+            wire.input.0,
+            wire.output.0,
+            wire.elbow.name()
+        ));
+        for point in &wire.via {
+            out.push_str(&format!(" {},{}", point.x, point.y));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn parse_field<T: std::str::FromStr>(field: Option<&str>, what: &str) -> Result<T, String> {
+    field
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| format!("Expected {what}"))
+}
+
+fn load_node(graph: &mut Graph, mut fields: SplitWhitespace) -> Result<(), String> {
+    let coords = Coords {
+        x: parse_field(fields.next(), "x coordinate")?,
+        y: parse_field(fields.next(), "y coordinate")?,
+    };
+    let gate_name = fields.next().ok_or("Expected gate")?;
+    let gate = Gate::from_name(gate_name).ok_or_else(|| format!("Unknown gate `{gate_name}`"))?;
+    let id = graph.add_node(&gate, &coords);
+    graph.node_mut(id).name = fields.next().map(String::from);
+    Ok(())
+}
+
+fn load_wire(graph: &mut Graph, mut fields: SplitWhitespace) -> Result<(), String> {
+    let input: usize = parse_field(fields.next(), "input node")?;
+    let output: usize = parse_field(fields.next(), "output node")?;
+    let num_nodes = graph.nodes().count();
+    if input >= num_nodes || output >= num_nodes {
+        return Err("Wire refers to a node that doesn't exist".to_string());
+    }
+    graph.check_inputs(NodeId(output))?;
+    let elbow_name = fields.next().ok_or("Expected elbow")?;
+    let elbow =
+        Elbow::from_name(elbow_name).ok_or_else(|| format!("Unknown elbow `{elbow_name}`"))?;
+    let via = fields
+        .map(|point| {
+            let (x, y) = point.split_once(',').unzip();
+            Ok(Coords {
+                x: parse_field(x, "point as `x,y`")?,
+                y: parse_field(y, "point as `x,y`")?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    let id = graph.add_wire(NodeId(input), NodeId(output), &elbow);
+    graph.wire_mut(id).via = via;
+    Ok(())
+}
+
+pub fn load(s: &str) -> Result<Graph, String> {
+    let mut lines = s.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        _ => return Err("Not an Electron Architect save file".to_string()),
+    }
+
+    let mut graph = Graph::new();
+    for (i, line) in lines {
+        let mut fields = line.split_whitespace();
+        let result = match fields.next() {
+            None => Ok(()),
+            Some("node") => load_node(&mut graph, fields),
+            Some("wire") => load_wire(&mut graph, fields),
+            Some(other) => Err(format!("Unknown item `{other}`")),
+        };
+        result.map_err(|e| format!("Line {}: {e}", i + 1))?;
+    }
+
+    Ok(graph)
+}
+
+pub fn save_file(graph: &Graph, path: &str) -> Result<(), String> {
+    fs::write(path, save(graph)).map_err(|e| format!("Couldn't write `{path}`: {e}"))
+}
+
+pub fn load_file(path: &str) -> Result<Graph, String> {
+    let s = fs::read_to_string(path).map_err(|e| format!("Couldn't read `{path}`: {e}"))?;
+    load(&s).map_err(|e| format!("`{path}`: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{
+        gate::{Gate0, Gate1, GateN},
+        wire::WireId,
+    };
+
+    const TEST_FILE: &str = "electron-architect-rust 1
+node 0 0 buffer a
+node 0 2 always
+node 4 1 xnor y
+wire 0 2 diag_e
+wire 1 2 horz_s 2,3 4,3
+";
+
+    #[test]
+    fn test_round_trip() {
+        let graph = load(TEST_FILE).unwrap();
+        assert_eq!(save(&graph), TEST_FILE);
+    }
+
+    #[test]
+    fn test_load() {
+        let graph = load(TEST_FILE).unwrap();
+        let y = graph.find_node_by_name("y").unwrap();
+        assert_eq!(graph.node(y).gate, Gate::GN(GateN::Xnor));
+        assert_eq!(graph.node(NodeId(0)).gate, Gate::G1(Gate1::Buffer));
+        assert_eq!(graph.node(NodeId(1)).gate, Gate::G0(Gate0::Always));
+        assert_eq!(graph.node(NodeId(1)).name, None);
+        assert_eq!(graph.wires().count(), 2);
+        assert_eq!(
+            graph.wire(WireId(1)).via,
+            vec![Coords { x: 2, y: 3 }, Coords { x: 4, y: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert!(load("node 0 0 buffer").is_err());
+        assert!(load("electron-architect-rust 1\nnode 0 buffer").is_err());
+        assert!(load("electron-architect-rust 1\nnode 0 0 latch").is_err());
+        assert!(load("electron-architect-rust 1\nnode 0 0 buffer\nwire 0 1 horz_s").is_err());
+        assert!(load("electron-architect-rust 1\nnode 0 0 buffer\nwire 0 0 zigzag").is_err());
+        assert!(load("electron-architect-rust 1\nnode 0 0 buffer\nwire 0 0 horz_s 1").is_err());
+        assert!(load("electron-architect-rust 1\ngroup").is_err());
+        let full = "electron-architect-rust 1\nnode 0 0 buffer\nnode 2 0 always\nwire 0 1 horz_s";
+        assert!(load(full).is_err_and(|e| e.contains("can't take more than 0 inputs")));
+    }
+}
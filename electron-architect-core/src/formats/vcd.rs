@@ -0,0 +1,166 @@
+//! Value Change Dump, the waveform format read by GTKWave.
+
+use std::{collections::VecDeque, fmt::Write};
+
+use crate::{
+    formats::netlist::{identifier, node_identifier},
+    graph::{node::NodeId, Graph},
+};
+
+/// How many changes a recording keeps before dropping the oldest.
+pub const MAX_RECORDED_CHANGES: usize = 100_000;
+
+/// Short name VCD uses for a signal in each value change, from printable ASCII.
+fn id_code(mut i: usize) -> String {
+    const FIRST: u8 = b'!';
+    const COUNT: usize = (b'~' - FIRST + 1) as usize;
+    let mut code = String::new();
+    loop {
+        code.push((FIRST + (i % COUNT) as u8) as char);
+        i /= COUNT;
+        if i == 0 {
+            return code;
+        }
+        i -= 1;
+    }
+}
+
+/// Node outputs from each tick on which any of them changed.
+pub struct Recorder {
+    signals: Vec<NodeId>,
+    capacity: usize,
+    changes: VecDeque<(u64, Vec<bool>)>,
+}
+
+impl Recorder {
+    pub fn new(signals: Vec<NodeId>, capacity: usize) -> Self {
+        Self {
+            signals,
+            capacity,
+            changes: VecDeque::new(),
+        }
+    }
+
+    /// `state` is the output of every node, indexed by [`NodeId`].
+    pub fn sample(&mut self, tick: u64, state: &[bool]) {
+        let values: Vec<bool> = self
+            .signals
+            .iter()
+            .map(|id| state.get(id.0).copied().unwrap_or(false))
+            .collect();
+        if self.changes.back().is_some_and(|(_, last)| *last == values) {
+            return;
+        }
+        if self.changes.len() == self.capacity {
+            self.changes.pop_front();
+        }
+        self.changes.push_back((tick, values));
+    }
+
+    pub fn signals(&self) -> &[NodeId] {
+        &self.signals
+    }
+
+    /// Number of ticks on which something changed, up to the capacity.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// First and last ticks recorded.
+    pub fn span(&self) -> Option<(u64, u64)> {
+        Some((self.changes.front()?.0, self.changes.back()?.0))
+    }
+
+    /// One tick is written as one nanosecond.
+    pub fn to_vcd(&self, graph: &Graph, scope: &str) -> String {
+        let mut out = String::new();
+        writeln!(out, "$version Electron Architect $end").unwrap();
+        writeln!(out, "$timescale 1ns $end").unwrap();
+        writeln!(out, "$scope module {} $end", identifier(scope)).unwrap();
+        for (i, &id) in self.signals.iter().enumerate() {
+            let name = match &graph.node(id).name {
+                Some(name) => identifier(name),
+                None => node_identifier(graph, id),
+            };
+            writeln!(out, "$var wire 1 {} {name} $end", id_code(i)).unwrap();
+        }
+        writeln!(out, "$upscope $end").unwrap();
+        writeln!(out, "$enddefinitions $end").unwrap();
+
+        let mut previous: Option<&Vec<bool>> = None;
+        for (tick, values) in &self.changes {
+            writeln!(out, "#{tick}").unwrap();
+            if previous.is_none() {
+                writeln!(out, "$dumpvars").unwrap();
+            }
+            for (i, &value) in values.iter().enumerate() {
+                if previous.is_none_or(|previous| previous[i] != value) {
+                    writeln!(out, "{}{}", value as u8, id_code(i)).unwrap();
+                }
+            }
+            if previous.is_none() {
+                writeln!(out, "$end").unwrap();
+            }
+            previous = Some(values);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::save;
+
+    #[test]
+    fn test_id_code() {
+        assert_eq!(id_code(0), "!");
+        assert_eq!(id_code(93), "~");
+        assert_eq!(id_code(94), "!!");
+        assert_eq!(id_code(95), "\"!");
+    }
+
+    #[test]
+    fn test_vcd() {
+        let graph =
+            save::load("electron-architect-rust 1\nnode 0 0 buffer a\nnode 1 0 nor").unwrap();
+        let mut recorder = Recorder::new(vec![NodeId(0), NodeId(1)], 8);
+        recorder.sample(1, &[false, true]);
+        recorder.sample(2, &[false, true]);
+        recorder.sample(3, &[true, true]);
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(recorder.span(), Some((1, 3)));
+        assert_eq!(
+            recorder.to_vcd(&graph, "test"),
+            "$version Electron Architect $end
+$timescale 1ns $end
+$scope module test $end
+$var wire 1 ! a $end
+$var wire 1 \" n1_1_0 $end
+$upscope $end
+$enddefinitions $end
+#1
+$dumpvars
+0!
+1\"
+$end
+#3
+1!
+"
+        );
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut recorder = Recorder::new(vec![NodeId(0)], 3);
+        for tick in 0..10 {
+            recorder.sample(tick, &[tick % 2 == 0]);
+        }
+        assert_eq!(recorder.len(), 3);
+        assert_eq!(recorder.span(), Some((7, 9)));
+    }
+}
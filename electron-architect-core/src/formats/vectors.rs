@@ -0,0 +1,326 @@
+//! Test vector scripts, which drive a circuit's named inputs and check its named outputs.
+//!
+//! ```text
+//! # Comment
+//! circuit half_adder.ea
+//! inputs a b
+//! outputs sum carry
+//! ticks 4
+//! 0 1 -> 1 0
+//! 11  -> 0 x
+//! ```
+//!
+//! Each vector holds its inputs for `ticks` ticks, then checks the outputs, where `x`
+//! matches anything. Without `ticks`, loop-free circuits are given just long enough to settle.
+//! The circuit path is relative to the script.
+
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use crate::{
+    formats::save,
+    graph::{node::NodeId, sim::Simulation, Graph},
+};
+
+struct Vector {
+    line: usize,
+    inputs: Vec<bool>,
+    /// `None` for don't care
+    outputs: Vec<Option<bool>>,
+}
+
+pub struct TestVectors {
+    pub circuit: Option<String>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    ticks: Option<u64>,
+    vectors: Vec<Vector>,
+}
+
+fn parse_values(s: &str) -> Vec<Option<bool>> {
+    s.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| match ch {
+            '0' => Some(Some(false)),
+            '1' => Some(Some(true)),
+            'x' | 'X' => Some(None),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+impl TestVectors {
+    fn parse_vector(&self, line: usize, s: &str) -> Result<Vector, String> {
+        let (inputs, outputs) = s
+            .split_once("->")
+            .ok_or("Expected `<inputs> -> <outputs>`")?;
+        let inputs = parse_values(inputs);
+        let outputs = parse_values(outputs);
+        if inputs.len() != self.inputs.len() || inputs.contains(&None) {
+            return Err(format!(
+                "Expected {} input values of 0 or 1",
+                self.inputs.len()
+            ));
+        }
+        if outputs.len() != self.outputs.len() {
+            return Err(format!(
+                "Expected {} output values of 0, 1 or x",
+                self.outputs.len()
+            ));
+        }
+        Ok(Vector {
+            line,
+            inputs: inputs.into_iter().flatten().collect(),
+            outputs,
+        })
+    }
+}
+
+impl FromStr for TestVectors {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut script = TestVectors {
+            circuit: None,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            ticks: None,
+            vectors: Vec::new(),
+        };
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let (keyword, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let names = || args.split_whitespace().map(String::from).collect();
+            let result = match keyword {
+                "" => Ok(()),
+                "circuit" => {
+                    script.circuit = Some(args.trim().to_string());
+                    Ok(())
+                }
+                "inputs" => {
+                    script.inputs = names();
+                    Ok(())
+                }
+                "outputs" => {
+                    script.outputs = names();
+                    Ok(())
+                }
+                "ticks" => match args.trim().parse() {
+                    Ok(ticks) if ticks > 0 => {
+                        script.ticks = Some(ticks);
+                        Ok(())
+                    }
+                    _ => Err("Expected a number of ticks of at least 1".to_string()),
+                },
+                _ => script
+                    .parse_vector(i + 1, line)
+                    .map(|vector| script.vectors.push(vector)),
+            };
+            result.map_err(|e| format!("Line {}: {e}", i + 1))?;
+        }
+        if script.outputs.is_empty() {
+            return Err("Script has no `outputs` to check".to_string());
+        }
+        Ok(script)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Line of the script the vector is on
+    pub line: usize,
+    pub tick: u64,
+    pub output: String,
+    pub expected: bool,
+    pub actual: bool,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tick {} (line {}): expected {}={} but got {}",
+            self.tick, self.line, self.output, self.expected as u8, self.actual as u8
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Report {
+    /// Vectors checked, including any that failed
+    pub vectors: usize,
+    pub ticks: u64,
+    /// The first failure, which stops the run
+    pub mismatch: Option<Mismatch>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.mismatch.is_none()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mismatch {
+            None => write!(
+                f,
+                "PASS: {} vectors over {} ticks",
+                self.vectors, self.ticks
+            ),
+            Some(mismatch) => write!(f, "FAIL at {mismatch}"),
+        }
+    }
+}
+
+fn find_ports(graph: &Graph, names: &[String]) -> Result<Vec<NodeId>, String> {
+    names
+        .iter()
+        .map(|name| {
+            graph
+                .find_node_by_name(name)
+                .ok_or_else(|| format!("No node named `{name}`"))
+        })
+        .collect()
+}
+
+impl TestVectors {
+    /// Simulates `graph` from its initial state through every vector.
+    pub fn run(&self, graph: &Graph) -> Result<Report, String> {
+        let inputs = find_ports(graph, &self.inputs)?;
+        let outputs = find_ports(graph, &self.outputs)?;
+        let ticks = match self.ticks {
+            Some(ticks) => ticks,
+            None => graph.settle_ticks().ok_or(
+                "The circuit has loops, so the script needs to say how many `ticks` each vector takes",
+            )?,
+        };
+
+        let mut simulation = Simulation::new();
+        for (i, vector) in self.vectors.iter().enumerate() {
+            for (&id, &value) in inputs.iter().zip(&vector.inputs) {
+                simulation.drive(id, value);
+            }
+            for _ in 0..ticks {
+                simulation.step(graph);
+            }
+
+            let mismatch = outputs
+                .iter()
+                .zip(&vector.outputs)
+                .zip(&self.outputs)
+                .find_map(|((&id, &expected), name)| {
+                    let actual = simulation.output(id);
+                    let expected = expected?;
+                    (actual != expected).then(|| Mismatch {
+                        line: vector.line,
+                        tick: simulation.tick(),
+                        output: name.clone(),
+                        expected,
+                        actual,
+                    })
+                });
+            if mismatch.is_some() {
+                return Ok(Report {
+                    vectors: i + 1,
+                    ticks: simulation.tick(),
+                    mismatch,
+                });
+            }
+        }
+        Ok(Report {
+            vectors: self.vectors.len(),
+            ticks: simulation.tick(),
+            mismatch: None,
+        })
+    }
+}
+
+/// Runs the script at `path` against its own circuit, or `graph` if it doesn't name one.
+pub fn run_file(path: &str, graph: &Graph) -> Result<Report, String> {
+    let s = fs::read_to_string(path).map_err(|e| format!("Couldn't read `{path}`: {e}"))?;
+    let script: TestVectors = s.parse().map_err(|e| format!("`{path}`: {e}"))?;
+    match &script.circuit {
+        Some(circuit) => {
+            let circuit = Path::new(path).with_file_name(circuit);
+            let circuit = save::load_file(&circuit.to_string_lossy())?;
+            script.run(&circuit)
+        }
+        None => script.run(graph),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn half_adder() -> Graph {
+        save::load(include_str!("golden/half_adder.ea")).unwrap()
+    }
+
+    #[test]
+    fn test_golden_half_adder() {
+        let script: TestVectors = include_str!("golden/half_adder.tv").parse().unwrap();
+        assert_eq!(script.circuit.as_deref(), Some("half_adder.ea"));
+        let report = script.run(&half_adder()).unwrap();
+        assert!(report.passed(), "{report}");
+        assert_eq!(report.vectors, 4);
+    }
+
+    #[test]
+    fn test_run_file() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/formats/golden/half_adder.tv"
+        );
+        let report = run_file(path, &Graph::new()).unwrap();
+        assert!(report.passed(), "{report}");
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let script: TestVectors = "inputs a b\noutputs sum and\nticks 2\n00 -> 0x\n11 -> 1 1"
+            .parse()
+            .unwrap();
+        let report = script.run(&half_adder()).unwrap();
+        assert_eq!(
+            report.mismatch,
+            Some(Mismatch {
+                line: 5,
+                tick: 4,
+                output: "sum".to_string(),
+                expected: true,
+                actual: false,
+            })
+        );
+        assert_eq!(
+            report.to_string(),
+            "FAIL at tick 4 (line 5): expected sum=1 but got 0"
+        );
+    }
+
+    #[test]
+    fn test_too_few_ticks() {
+        // The nand is three gates deep, so its output hasn't caught up after one tick
+        let script: TestVectors = "outputs and\nticks 1\n -> 1".parse().unwrap();
+        assert!(!script.run(&half_adder()).unwrap().passed());
+        let script: TestVectors = "outputs and\n -> 1".parse().unwrap();
+        assert!(script.run(&half_adder()).unwrap().passed());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("inputs a\n0 -> 1".parse::<TestVectors>().is_err());
+        assert!("inputs a\noutputs y\n0 1 -> 1"
+            .parse::<TestVectors>()
+            .is_err());
+        assert!("inputs a\noutputs y\nx -> 1"
+            .parse::<TestVectors>()
+            .is_err());
+        assert!("inputs a\noutputs y\n0 -> 2"
+            .parse::<TestVectors>()
+            .is_err());
+        assert!("outputs y\nticks 0".parse::<TestVectors>().is_err());
+        assert!("outputs y\n0 1".parse::<TestVectors>().is_err());
+    }
+}
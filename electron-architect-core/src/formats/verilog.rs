@@ -0,0 +1,214 @@
+use std::fmt::Write;
+
+use crate::{
+    formats::netlist::{Driver, Netlist},
+    graph::Graph,
+};
+
+/// Verilog-2005 keywords, which can't be used as signal names.
+const RESERVED: &[&str] = &[
+    "always",
+    "and",
+    "assign",
+    "automatic",
+    "begin",
+    "buf",
+    "bufif0",
+    "bufif1",
+    "case",
+    "casex",
+    "casez",
+    "cell",
+    "cmos",
+    "config",
+    "deassign",
+    "default",
+    "defparam",
+    "design",
+    "disable",
+    "edge",
+    "else",
+    "end",
+    "endcase",
+    "endconfig",
+    "endfunction",
+    "endgenerate",
+    "endmodule",
+    "endprimitive",
+    "endspecify",
+    "endtable",
+    "endtask",
+    "event",
+    "for",
+    "force",
+    "forever",
+    "fork",
+    "function",
+    "generate",
+    "genvar",
+    "highz0",
+    "highz1",
+    "if",
+    "ifnone",
+    "incdir",
+    "include",
+    "initial",
+    "inout",
+    "input",
+    "instance",
+    "integer",
+    "join",
+    "large",
+    "liblist",
+    "library",
+    "localparam",
+    "macromodule",
+    "medium",
+    "module",
+    "nand",
+    "negedge",
+    "nmos",
+    "nor",
+    "noshowcancelled",
+    "not",
+    "notif0",
+    "notif1",
+    "or",
+    "output",
+    "parameter",
+    "pmos",
+    "posedge",
+    "primitive",
+    "pull0",
+    "pull1",
+    "pulldown",
+    "pullup",
+    "pulsestyle_ondetect",
+    "pulsestyle_onevent",
+    "rcmos",
+    "real",
+    "realtime",
+    "reg",
+    "release",
+    "repeat",
+    "rnmos",
+    "rpmos",
+    "rtran",
+    "rtranif0",
+    "rtranif1",
+    "scalared",
+    "showcancelled",
+    "signed",
+    "small",
+    "specify",
+    "specparam",
+    "strong0",
+    "strong1",
+    "supply0",
+    "supply1",
+    "table",
+    "task",
+    "time",
+    "tran",
+    "tranif0",
+    "tranif1",
+    "tri",
+    "tri0",
+    "tri1",
+    "triand",
+    "trior",
+    "trireg",
+    "unsigned",
+    "use",
+    "uwire",
+    "vectored",
+    "wait",
+    "wand",
+    "weak0",
+    "weak1",
+    "while",
+    "wire",
+    "wor",
+    "xnor",
+    "xor",
+];
+
+/// Structural Verilog, with one gate primitive per node and named inputs and outputs as ports.
+pub fn export(graph: &Graph, module: &str) -> Result<String, String> {
+    let netlist = Netlist::build(graph, module, RESERVED)?;
+    let mut out = String::new();
+
+    let ports: Vec<String> = (netlist.inputs.iter().map(|&id| ("input", id)))
+        .chain(netlist.outputs.iter().map(|&id| ("output", id)))
+        .map(|(direction, id)| format!("    {direction} {}", netlist.signal_name(id)))
+        .collect();
+    writeln!(out, "module {} (", netlist.name).unwrap();
+    if !ports.is_empty() {
+        writeln!(out, "{}", ports.join(",\n")).unwrap();
+    }
+    writeln!(out, ");").unwrap();
+
+    for (_, signal) in netlist.internal_signals() {
+        writeln!(out, "    wire {};", signal.name).unwrap();
+    }
+    writeln!(out).unwrap();
+
+    for signal in &netlist.signals {
+        let name = &signal.name;
+        match &signal.driver {
+            Driver::Input => {}
+            Driver::Const(value) => {
+                writeln!(out, "    assign {name} = 1'b{};", *value as u8).unwrap();
+            }
+            Driver::Buffer(input) => {
+                writeln!(out, "    buf ({name}, {});", netlist.signal_name(*input)).unwrap();
+            }
+            Driver::Gate(gate, inputs) => {
+                let inputs: Vec<&str> = inputs.iter().map(|&id| netlist.signal_name(id)).collect();
+                writeln!(out, "    {} ({name}, {});", gate.name(), inputs.join(", ")).unwrap();
+            }
+        }
+    }
+
+    writeln!(out, "endmodule").unwrap();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formats::save,
+        graph::{
+            gate::{Gate, Gate0},
+            node::NodeId,
+        },
+    };
+
+    #[test]
+    fn test_golden_half_adder() {
+        let graph = save::load(include_str!("golden/half_adder.ea")).unwrap();
+        assert_eq!(
+            export(&graph, "half_adder").unwrap(),
+            include_str!("golden/half_adder.v")
+        );
+    }
+
+    #[test]
+    fn test_wire_into_constant() {
+        let mut graph = save::load(
+            "electron-architect-rust 1\nnode 0 0 buffer a\nnode 2 0 buffer y\nwire 0 1 horz_s",
+        )
+        .unwrap();
+        // Constants can't be wired into, but a wired node can become one
+        graph.node_mut(NodeId(1)).gate = Gate::G0(Gate0::Always);
+        let verilog = export(&graph, "constant").unwrap();
+        assert!(verilog.contains("assign y = 1'b1;"), "{verilog}");
+    }
+
+    #[test]
+    fn test_random_is_rejected() {
+        let graph = save::load("electron-architect-rust 1\nnode 0 0 random y").unwrap();
+        assert!(export(&graph, "random").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt::Write;
+
+use crate::{
+    formats::netlist::{Driver, Netlist},
+    graph::{gate::GateN, Graph},
+};
+
+const ARCHITECTURE: &str = "structural";
+
+/// VHDL-2008 reserved words, plus the architecture name and the types used.
+const RESERVED: &[&str] = &[
+    "abs",
+    "access",
+    "after",
+    "alias",
+    "all",
+    "and",
+    "architecture",
+    "array",
+    "assert",
+    "assume",
+    "attribute",
+    "begin",
+    "block",
+    "body",
+    "buffer",
+    "bus",
+    "case",
+    "component",
+    "configuration",
+    "constant",
+    "context",
+    "cover",
+    "default",
+    "disconnect",
+    "downto",
+    "else",
+    "elsif",
+    "end",
+    "entity",
+    "exit",
+    "fairness",
+    "file",
+    "for",
+    "force",
+    "function",
+    "generate",
+    "generic",
+    "group",
+    "guarded",
+    "if",
+    "impure",
+    "in",
+    "inertial",
+    "inout",
+    "is",
+    "label",
+    "library",
+    "linkage",
+    "literal",
+    "loop",
+    "map",
+    "mod",
+    "nand",
+    "new",
+    "next",
+    "nor",
+    "not",
+    "null",
+    "of",
+    "on",
+    "open",
+    "or",
+    "others",
+    "out",
+    "package",
+    "parameter",
+    "port",
+    "postponed",
+    "procedure",
+    "process",
+    "property",
+    "protected",
+    "pure",
+    "range",
+    "record",
+    "register",
+    "reject",
+    "release",
+    "rem",
+    "report",
+    "restrict",
+    "return",
+    "rol",
+    "ror",
+    "select",
+    "sequence",
+    "severity",
+    "shared",
+    "signal",
+    "sla",
+    "sll",
+    "sra",
+    "srl",
+    "strong",
+    "subtype",
+    "then",
+    "to",
+    "transport",
+    "type",
+    "unaffected",
+    "units",
+    "until",
+    "use",
+    "variable",
+    "vmode",
+    "vprop",
+    "vunit",
+    "wait",
+    "when",
+    "while",
+    "with",
+    "xnor",
+    "xor",
+    "ieee",
+    "std_logic",
+    "std_logic_1164",
+    ARCHITECTURE,
+];
+
+/// The right hand side of a gate's signal assignment.
+/// VHDL has no n-input primitives, so the operator is repeated between every input.
+fn gate_expression(gate: GateN, inputs: &[&str]) -> String {
+    let (op, negated) = match gate {
+        GateN::And => ("and", false),
+        GateN::Nand => ("and", true),
+        GateN::Or => ("or", false),
+        GateN::Nor => ("or", true),
+        GateN::Xor => ("xor", false),
+        GateN::Xnor => ("xor", true),
+    };
+    let expr = inputs.join(&format!(" {op} "));
+    match negated {
+        true => format!("not ({expr})"),
+        false => expr,
+    }
+}
+
+/// An entity with named inputs and outputs as `std_logic` ports,
+/// and a dataflow architecture with one assignment per node.
+pub fn export(graph: &Graph, entity: &str) -> Result<String, String> {
+    let netlist = Netlist::build(graph, entity, RESERVED)?;
+    let name = &netlist.name;
+    let mut out = String::new();
+
+    writeln!(out, "library ieee;").unwrap();
+    writeln!(out, "use ieee.std_logic_1164.all;").unwrap();
+    writeln!(out).unwrap();
+
+    let ports: Vec<String> = (netlist.inputs.iter().map(|&id| ("in", id)))
+        .chain(netlist.outputs.iter().map(|&id| ("out", id)))
+        .map(|(mode, id)| format!("        {} : {mode} std_logic", netlist.signal_name(id)))
+        .collect();
+    writeln!(out, "entity {name} is").unwrap();
+    // An empty port clause isn't allowed
+    if !ports.is_empty() {
+        writeln!(out, "    port (").unwrap();
+        writeln!(out, "{}", ports.join(";\n")).unwrap();
+        writeln!(out, "    );").unwrap();
+    }
+    writeln!(out, "end entity {name};").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "architecture {ARCHITECTURE} of {name} is").unwrap();
+    for (_, signal) in netlist.internal_signals() {
+        writeln!(out, "    signal {} : std_logic;", signal.name).unwrap();
+    }
+    writeln!(out, "begin").unwrap();
+
+    for signal in &netlist.signals {
+        let value = match &signal.driver {
+            Driver::Input => continue,
+            Driver::Const(value) => format!("'{}'", *value as u8),
+            Driver::Buffer(input) => netlist.signal_name(*input).to_string(),
+            Driver::Gate(gate, inputs) => {
+                let inputs: Vec<&str> = inputs.iter().map(|&id| netlist.signal_name(id)).collect();
+                gate_expression(*gate, &inputs)
+            }
+        };
+        writeln!(out, "    {} <= {value};", signal.name).unwrap();
+    }
+
+    writeln!(out, "end architecture {ARCHITECTURE};").unwrap();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::save;
+
+    #[test]
+    fn test_golden_half_adder() {
+        let graph = save::load(include_str!("golden/half_adder.ea")).unwrap();
+        assert_eq!(
+            export(&graph, "half_adder").unwrap(),
+            include_str!("golden/half_adder.vhd")
+        );
+    }
+
+    #[test]
+    fn test_gate_expression() {
+        assert_eq!(gate_expression(GateN::Or, &["a"]), "a");
+        assert_eq!(gate_expression(GateN::Nor, &["a"]), "not (a)");
+        assert_eq!(
+            gate_expression(GateN::Xnor, &["a", "b", "c"]),
+            "not (a xor b xor c)"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Just enough XML to read other tools' files: elements and attributes.
+//! Text content, comments, processing instructions and doctypes are skipped.
+
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or("Unterminated entity reference")?;
+        let entity = &rest[start + 1..start + end];
+        let ch = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("Unknown entity `&{entity};`"))?
+            }
+        };
+        out.push(ch);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+struct Parser<'s> {
+    s: &'s str,
+    pos: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.s[self.pos..]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(format!("Expected `{token}`")),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves past `end`, wherever it next appears.
+    fn skip_past(&mut self, end: &str) -> Result<(), String> {
+        let i = self
+            .rest()
+            .find(end)
+            .ok_or_else(|| format!("Expected `{end}`"))?;
+        self.pos += i + end.len();
+        Ok(())
+    }
+
+    /// Skips to the next start or end tag.
+    fn skip_content(&mut self) -> Result<(), String> {
+        loop {
+            self.pos += self.rest().find('<').unwrap_or(self.rest().len());
+            if self.eat("<!--") {
+                self.skip_past("-->")?;
+            } else if self.eat("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else if self.eat("<?") {
+                self.skip_past("?>")?;
+            } else if self.eat("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|ch: char| !(ch.is_alphanumeric() || "_-:.".contains(ch)))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err("Expected a name".to_string());
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn attribute_value(&mut self) -> Result<String, String> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err("Expected a quoted attribute value".to_string()),
+        };
+        self.pos += 1;
+        let len = self
+            .rest()
+            .find(quote)
+            .ok_or("Unterminated attribute value")?;
+        let value = unescape(&self.rest()[..len])?;
+        self.pos += len + 1;
+        Ok(value)
+    }
+
+    fn element(&mut self) -> Result<Element, String> {
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            if self.eat(">") {
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            attributes.push((key, self.attribute_value()?));
+        }
+
+        let mut children = Vec::new();
+        loop {
+            self.skip_content()?;
+            if self.eat("</") {
+                let close = self.name()?;
+                if close != name {
+                    return Err(format!("`<{name}>` closed by `</{close}>`"));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children,
+                });
+            }
+            if self.rest().is_empty() {
+                return Err(format!("`<{name}>` is never closed"));
+            }
+            children.push(self.element()?);
+        }
+    }
+}
+
+/// The root element of a document.
+pub fn parse(s: &str) -> Result<Element, String> {
+    let mut parser = Parser { s, pos: 0 };
+    let result = parser.skip_content().and_then(|_| parser.element());
+    result.map_err(|e| {
+        let line = s[..parser.pos.min(s.len())].matches('\n').count() + 1;
+        format!("Line {line}: {e}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>
+<!-- comment -->
+<project version='1.0'>
+  <a name=\"label\" val=\"x &amp; &#121;\"/>
+  text
+  <comp loc=\"(10,20)\"><a name=\"facing\" val=\"west\"/></comp >
+</project>",
+        )
+        .unwrap();
+        assert_eq!(root.name, "project");
+        assert_eq!(root.attribute("version"), Some("1.0"));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].attribute("val"), Some("x & y"));
+        let comp = root.children_named("comp").next().unwrap();
+        assert_eq!(comp.children[0].attribute("val"), Some("west"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<a b=c/>").is_err());
+        assert!(parse("<a b=\"&nope;\"/>").is_err());
+        assert_eq!(
+            parse("<a>\n\n<b></c>").err().unwrap(),
+            "Line 3: `<b>` closed by `</c>`"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+pub mod elbow;
+pub mod equiv;
+pub mod eval;
+pub mod expr;
+pub mod gate;
+pub mod loops;
+pub mod minimise;
+pub mod node;
+pub mod plugin;
+pub mod route;
+pub mod sim;
+pub mod synth;
+pub mod truth_table;
+pub mod wire;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "raylib")]
+use raylib::prelude::*;
+
+use crate::coords::Coords;
+use crate::graph::{
+    elbow::Elbow,
+    gate::Gate,
+    node::{Node, NodeId},
+    wire::{Wire, WireId},
+};
+
+pub struct Graph {
+    nodes: Vec<Node>,
+    wires: Vec<Wire>,
+    /// Wires into each node, indexed by [`NodeId`].
+    num_inputs: Vec<usize>,
+    revision: u64,
+}
+
+/// Shared by every graph, so that no two share a revision.
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            wires: Vec::new(),
+            num_inputs: Vec::new(),
+            revision: next_revision(),
+        }
+    }
+
+    /// Changes whenever the graph might have, and is never the same for two graphs,
+    /// so anything worked out from the graph can be kept until it changes.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn add_node(&mut self, gate: &Gate, coords: &Coords) -> NodeId {
+        self.revision = next_revision();
+        self.nodes.push(Node::new(gate, coords));
+        self.num_inputs.push(0);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Panics if `dest` already has all the inputs its gate uses, see [`Graph::check_inputs`].
+    /// Wires that loop straight back or duplicate another are allowed, though the editor avoids them.
+    pub fn add_wire(&mut self, src: NodeId, dest: NodeId, elbow: &Elbow) -> WireId {
+        if let Err(e) = self.check_inputs(dest) {
+            panic!("{e}");
+        }
+        self.revision = next_revision();
+        self.num_inputs[dest.0] += 1;
+        self.wires.push(Wire::new(src, dest, elbow));
+        WireId(self.wires.len() - 1)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.revision = next_revision();
+        &mut self.nodes[id.0]
+    }
+
+    pub fn wire(&self, id: WireId) -> &Wire {
+        &self.wires[id.0]
+    }
+
+    /// For reshaping the wire. Its ends stay the nodes it was added between.
+    pub fn wire_mut(&mut self, id: WireId) -> &mut Wire {
+        self.revision = next_revision();
+        &mut self.wires[id.0]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (NodeId(i), node))
+    }
+
+    pub fn wires(&self) -> impl Iterator<Item = (WireId, &Wire)> {
+        self.wires
+            .iter()
+            .enumerate()
+            .map(|(i, wire)| (WireId(i), wire))
+    }
+
+    /// Named nodes with nothing wired into them, which act as the circuit's inputs.
+    /// 0-gates are constants rather than inputs.
+    pub fn input_ports(&self) -> Vec<NodeId> {
+        self.nodes()
+            .filter(|(id, node)| {
+                node.name.is_some()
+                    && !matches!(node.gate, Gate::G0(_))
+                    && !self.wires.iter().any(|wire| wire.output == *id)
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Named nodes that aren't wired into anything, which act as the circuit's outputs.
+    pub fn output_ports(&self) -> Vec<NodeId> {
+        let inputs = self.input_ports();
+        self.nodes()
+            .filter(|(id, node)| {
+                node.name.is_some()
+                    && !inputs.contains(id)
+                    && !self.wires.iter().any(|wire| wire.input == *id)
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    pub fn find_node_at_coords(&self, search_coords: &Coords) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .position(|node| node.coords == *search_coords)
+            .map(NodeId)
+    }
+
+    pub fn find_node_by_name(&self, name: &str) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .position(|node| node.name.as_deref() == Some(name))
+            .map(NodeId)
+    }
+
+    /// Wire from `src` to `dest`, if they're already wired.
+    pub fn find_wire(&self, src: NodeId, dest: NodeId) -> Option<WireId> {
+        self.wires
+            .iter()
+            .position(|wire| wire.input == src && wire.output == dest)
+            .map(WireId)
+    }
+
+    /// Why a wire from `src` to `dest` can't be added, if it can't:
+    /// it would loop straight back, duplicate a wire, or go into a gate that has all the inputs it uses.
+    pub fn check_wire(&self, src: NodeId, dest: NodeId) -> Result<(), String> {
+        let (start, end) = (self.node(src).coords, self.node(dest).coords);
+        if src == dest {
+            return Err(format!("Can't wire the node at {start} to itself"));
+        }
+        if self.find_wire(src, dest).is_some() {
+            return Err(format!("There's already a wire from {start} to {end}"));
+        }
+        self.check_inputs(dest)
+    }
+
+    /// Whether `dest` can take another input. Its gate would ignore any past the ones it uses.
+    pub fn check_inputs(&self, dest: NodeId) -> Result<(), String> {
+        let node = self.node(dest);
+        match self.num_inputs[dest.0] < node.gate.max_inputs() {
+            true => Ok(()),
+            false => Err(format!(
+                "The {} gate at {} can't take more than {} inputs",
+                node.gate.name(),
+                node.coords,
+                node.gate.max_inputs()
+            )),
+        }
+    }
+
+    pub fn find_wire_intersecting_coords(&self, search_coords: &Coords) -> Option<WireId> {
+        self.wires
+            .iter()
+            .position(|wire| {
+                let start = &self.node(wire.input).coords;
+                let end = &self.node(wire.output).coords;
+                wire.is_intersecting_coords(start, end, search_coords)
+            })
+            .map(WireId)
+    }
+}
+
+#[cfg(feature = "raylib")]
+impl Graph {
+    pub fn draw(&self, d: &mut impl RaylibDraw) {
+        for wire in &self.wires {
+            self.draw_wire(d, wire, Color::GRAY);
+        }
+        for node in &self.nodes {
+            node.draw(d);
+        }
+    }
+
+    fn draw_wire(&self, d: &mut impl RaylibDraw, wire: &Wire, color: Color) {
+        let start = &self.node(wire.input).coords;
+        let end = &self.node(wire.output).coords;
+        wire.draw(d, start, end, color);
+    }
+
+    /// Draws the given wires over the top of the graph.
+    pub fn draw_highlighted_wires(&self, d: &mut impl RaylibDraw, ids: &[WireId], color: Color) {
+        for &id in ids {
+            self.draw_wire(d, self.wire(id), color);
+        }
+    }
+
+    /// Lights up every node that's on in `state`, along with the wires they drive.
+    pub fn draw_state(&self, d: &mut impl RaylibDraw, state: &[bool], color: Color) {
+        let is_on = |id: NodeId| state.get(id.0).copied().unwrap_or(false);
+        for wire in self.wires.iter().filter(|wire| is_on(wire.input)) {
+            self.draw_wire(d, wire, color);
+        }
+        for (_, node) in self.nodes().filter(|&(id, _)| is_on(id)) {
+            node.gate.draw(d, &node.coords, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_graph() {
+        let graph = Graph::new();
+        assert_eq!(graph.nodes.len(), 0);
+    }
+
+    #[test]
+    fn test_add_node() {
+        let mut graph = Graph::new();
+
+        const TEST_GATE: Gate = Gate::G1(gate::Gate1::Buffer);
+        const TEST_COORDS: Coords = Coords { x: 0, y: 0 };
+
+        graph.add_node(&TEST_GATE, &TEST_COORDS);
+        assert_eq!(graph.nodes.len(), 1);
+        // Todo if ever they are made PartiallyEq
+        // assert_eq!(graph.nodes.last().unwrap().gate, TEST_GATE);
+        // assert_eq!(graph.nodes.last().unwrap().coords, TEST_COORDS);
+    }
+
+    #[test]
+    fn test_revision() {
+        let mut graph = Graph::new();
+        let first = graph.revision();
+        assert_ne!(Graph::new().revision(), first);
+
+        let id = graph.add_node(&Gate::G1(gate::Gate1::Buffer), &Coords { x: 0, y: 0 });
+        let added = graph.revision();
+        assert_ne!(added, first);
+        graph.node(id);
+        assert_eq!(graph.revision(), added);
+        graph.node_mut(id).name = Some("a".to_string());
+        assert_ne!(graph.revision(), added);
+    }
+
+    #[test]
+    fn test_find_wire() {
+        let mut graph = Graph::new();
+        let gate = Gate::G1(gate::Gate1::Buffer);
+        let a = graph.add_node(&gate, &Coords { x: 0, y: 0 });
+        let b = graph.add_node(&gate, &Coords { x: 2, y: 0 });
+        let wire = graph.add_wire(a, b, &Elbow::HorzS);
+
+        assert_eq!(graph.find_wire(a, b), Some(wire));
+        // Wires have a direction
+        assert_eq!(graph.find_wire(b, a), None);
+    }
+
+    #[test]
+    fn test_check_wire() {
+        let mut graph = Graph::new();
+        let gate = Gate::G1(gate::Gate1::Buffer);
+        let a = graph.add_node(&gate, &Coords { x: 0, y: 0 });
+        let b = graph.add_node(&gate, &Coords { x: 2, y: 1 });
+        let c = graph.add_node(&gate, &Coords { x: 4, y: 0 });
+        let always = graph.add_node(&Gate::G0(gate::Gate0::Always), &Coords { x: 6, y: 0 });
+        assert!(graph.check_wire(a, b).is_ok());
+        assert!(graph.check_wire(a, a).unwrap_err().contains("to itself"));
+
+        graph.add_wire(a, b, &Elbow::HorzS);
+        assert!(graph
+            .check_wire(a, b)
+            .unwrap_err()
+            .contains("already a wire"));
+        assert!(graph.check_wire(b, a).is_ok());
+        // A buffer only uses one input, and a constant none
+        assert!(graph.check_wire(c, b).unwrap_err().contains("more than 1"));
+        assert!(graph
+            .check_wire(a, always)
+            .unwrap_err()
+            .contains("more than 0"));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't take more than 1 inputs")]
+    fn test_add_wire_into_full_gate() {
+        let mut graph = Graph::new();
+        let gate = Gate::G1(gate::Gate1::Buffer);
+        let a = graph.add_node(&gate, &Coords { x: 0, y: 0 });
+        let b = graph.add_node(&gate, &Coords { x: 0, y: 2 });
+        let y = graph.add_node(&gate, &Coords { x: 2, y: 1 });
+        graph.add_wire(a, y, &Elbow::HorzS);
+        graph.add_wire(b, y, &Elbow::HorzS);
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(feature = "raylib")]
+use raylib::prelude::*;
+
+use crate::{common_traits::Scrollable, coords::Coords};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Elbow {
+    HorzS, // Horizontal start
+    VertS, // Vertical start
+    DiagS, // Diagonal start
+    DiagE, // Diagonal end
+}
+
+impl Scrollable for Elbow {
+    fn next(&self) -> Self {
+        use Elbow::*;
+        match *self {
+            HorzS => DiagE,
+            DiagE => VertS,
+            VertS => DiagS,
+            DiagS => HorzS,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        use Elbow::*;
+        match *self {
+            HorzS => DiagS,
+            DiagE => HorzS,
+            VertS => DiagE,
+            DiagS => VertS,
+        }
+    }
+}
+
+impl Elbow {
+    /// Stable identifier for the elbow, used in save files.
+    pub const fn name(&self) -> &'static str {
+        use Elbow::*;
+        match *self {
+            HorzS => "horz_s",
+            VertS => "vert_s",
+            DiagS => "diag_s",
+            DiagE => "diag_e",
+        }
+    }
+
+    pub const ALL: [Elbow; 4] = [Elbow::HorzS, Elbow::VertS, Elbow::DiagS, Elbow::DiagE];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|elbow| elbow.name() == name)
+    }
+
+    /// The point where a wire from `start` to `end` changes direction.
+    ///
+    /// Both segments are guaranteed to be horizontal, vertical or at 45 degrees.
+    pub fn joint(&self, start: &Coords, end: &Coords) -> Coords {
+        use Elbow::*;
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let diag = dx.abs().min(dy.abs());
+        match *self {
+            HorzS => Coords {
+                x: end.x,
+                y: start.y,
+            },
+            VertS => Coords {
+                x: start.x,
+                y: end.y,
+            },
+            DiagS => Coords {
+                x: start.x + dx.signum() * diag,
+                y: start.y + dy.signum() * diag,
+            },
+            DiagE => Coords {
+                x: end.x - dx.signum() * diag,
+                y: end.y - dy.signum() * diag,
+            },
+        }
+    }
+
+    /// Draws the path a wire from `start` to `end` takes.
+    #[cfg(feature = "raylib")]
+    pub fn draw(&self, d: &mut impl RaylibDraw, start: &Coords, end: &Coords, color: Color) {
+        let joint = self.joint(start, end);
+        d.draw_line_v(start.into_position(), joint.into_position(), color);
+        d.draw_line_v(joint.into_position(), end.into_position(), color);
+    }
+}
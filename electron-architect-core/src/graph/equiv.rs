@@ -0,0 +1,473 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    graph::{
+        gate::{Gate, Gate0, Gate1, GateN},
+        node::NodeId,
+        Graph,
+    },
+    sat::{Cnf, Lit, Solution},
+};
+
+/// Circuits with more inputs than this are compared with SAT rather than simulating every combination.
+pub const MAX_EXHAUSTIVE_INPUTS: usize = 12;
+/// SAT conflicts before giving up on a comparison, which keeps the editor responsive.
+pub const MAX_SAT_CONFLICTS: u64 = 100_000;
+/// Custom gates are encoded for SAT one clause per row of their truth table, so are limited in width.
+const MAX_CUSTOM_GATE_INPUTS: usize = 16;
+
+/// Input values under which the two circuits disagree.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Counterexample {
+    pub inputs: Vec<(String, bool)>,
+    pub output: String,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.inputs {
+            write!(f, "{name}={} ", *value as u8)?;
+        }
+        write!(
+            f,
+            "gives {output}={} on the left but {output}={} on the right",
+            self.left as u8,
+            self.right as u8,
+            output = self.output
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Equivalence {
+    Equivalent,
+    Different(Counterexample),
+    /// The SAT solver gave up after [`MAX_SAT_CONFLICTS`] conflicts.
+    Unknown,
+}
+
+/// Port names shared by both circuits, in the same order for each.
+struct MatchedPorts {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+fn port_names(graph: &Graph, ports: Vec<NodeId>) -> Vec<String> {
+    let mut names: Vec<String> = ports
+        .into_iter()
+        .map(|id| graph.node(id).name.clone().unwrap()) // Ports are always named
+        .collect();
+    names.sort();
+    names
+}
+
+fn match_ports(left: &Graph, right: &Graph) -> Result<MatchedPorts, String> {
+    let inputs = port_names(left, left.input_ports());
+    let outputs = port_names(left, left.output_ports());
+    if inputs != port_names(right, right.input_ports()) {
+        return Err("The circuits have different named inputs".to_string());
+    }
+    if outputs != port_names(right, right.output_ports()) {
+        return Err("The circuits have different named outputs".to_string());
+    }
+    if outputs.is_empty() {
+        return Err("The circuits have no named outputs to compare".to_string());
+    }
+    Ok(MatchedPorts { inputs, outputs })
+}
+
+fn ensure_deterministic(graph: &Graph) -> Result<(), String> {
+    match graph
+        .nodes()
+        .find(|(_, node)| node.gate == Gate::G0(Gate0::Random))
+    {
+        Some((id, _)) => Err(format!(
+            "{} is random, so equivalence can't be checked",
+            graph.node_label(id)
+        )),
+        None => Ok(()),
+    }
+}
+
+fn find_ports(graph: &Graph, names: &[String]) -> Vec<NodeId> {
+    names
+        .iter()
+        .map(|name| graph.find_node_by_name(name).unwrap()) // Already matched by name
+        .collect()
+}
+
+/// Compares the truth tables of both circuits row by row.
+fn check_exhaustive(
+    left: &Graph,
+    right: &Graph,
+    ports: &MatchedPorts,
+) -> Result<Equivalence, String> {
+    let left_table = left.truth_table(
+        &find_ports(left, &ports.inputs),
+        &find_ports(left, &ports.outputs),
+    )?;
+    let right_table = right.truth_table(
+        &find_ports(right, &ports.inputs),
+        &find_ports(right, &ports.outputs),
+    )?;
+
+    for (left_row, right_row) in left_table.rows.iter().zip(&right_table.rows) {
+        let mismatch = left_row
+            .outputs
+            .iter()
+            .zip(&right_row.outputs)
+            .position(|(l, r)| l != r);
+        if let Some(i) = mismatch {
+            return Ok(Equivalence::Different(Counterexample {
+                inputs: ports
+                    .inputs
+                    .iter()
+                    .cloned()
+                    .zip(left_row.inputs.clone())
+                    .collect(),
+                output: ports.outputs[i].clone(),
+                left: left_row.outputs[i],
+                right: right_row.outputs[i],
+            }));
+        }
+    }
+    Ok(Equivalence::Equivalent)
+}
+
+fn encode_equal(cnf: &mut Cnf, a: Lit, b: Lit) {
+    cnf.add_clause(vec![-a, b]);
+    cnf.add_clause(vec![a, -b]);
+}
+
+/// `out` is true exactly when every input is.
+fn encode_and(cnf: &mut Cnf, out: Lit, inputs: &[Lit]) {
+    for &input in inputs {
+        cnf.add_clause(vec![-out, input]);
+    }
+    let mut clause: Vec<Lit> = inputs.iter().map(|&input| -input).collect();
+    clause.push(out);
+    cnf.add_clause(clause);
+}
+
+fn encode_xor2(cnf: &mut Cnf, out: Lit, a: Lit, b: Lit) {
+    cnf.add_clause(vec![-out, a, b]);
+    cnf.add_clause(vec![-out, -a, -b]);
+    cnf.add_clause(vec![out, -a, b]);
+    cnf.add_clause(vec![out, a, -b]);
+}
+
+/// `out` is true when an odd number of inputs are, chained through two-input xors.
+fn encode_xor(cnf: &mut Cnf, out: Lit, inputs: &[Lit]) {
+    let mut acc = inputs[0];
+    for &input in &inputs[1..] {
+        let next = cnf.new_var();
+        encode_xor2(cnf, next, acc, input);
+        acc = next;
+    }
+    encode_equal(cnf, out, acc);
+}
+
+/// `out` equals `gate` applied to `inputs`, one clause per combination of inputs.
+fn encode_table(cnf: &mut Cnf, out: Lit, inputs: &[Lit], gate: &Gate) {
+    for row in 0u32..1 << inputs.len() {
+        let values: Vec<bool> = (0..inputs.len()).map(|i| row & (1 << i) != 0).collect();
+        // Either the inputs aren't this row, or the output matches it
+        let mut clause: Vec<Lit> = inputs
+            .iter()
+            .zip(&values)
+            .map(|(&input, &value)| if value { -input } else { input })
+            .collect();
+        let value = gate.evaluate(&values, &mut || false);
+        clause.push(if value { out } else { -out });
+        cnf.add_clause(clause);
+    }
+}
+
+/// Tseitin encoding: one variable per node, constrained to equal its gate's output.
+/// Input ports use the shared variable of the same name.
+fn encode_graph(
+    graph: &Graph,
+    cnf: &mut Cnf,
+    inputs: &HashMap<String, Lit>,
+) -> Result<Vec<Lit>, String> {
+    let input_ports = graph.input_ports();
+    let lits: Vec<Lit> = graph
+        .nodes()
+        .map(|(id, node)| match &node.name {
+            Some(name) if input_ports.contains(&id) => inputs[name],
+            _ => cnf.new_var(),
+        })
+        .collect();
+    let input_lists = graph.input_lists();
+
+    for (id, node) in graph.nodes() {
+        if input_ports.contains(&id) {
+            continue;
+        }
+        let out = lits[id.0];
+        // Any inputs beyond what the gate uses are ignored, as when it's evaluated
+        let ins: Vec<Lit> = input_lists[id.0]
+            .iter()
+            .take(node.gate.max_inputs())
+            .map(|input| lits[input.0])
+            .collect();
+        let constant = |cnf: &mut Cnf| {
+            let value = node.gate.evaluate(&[], &mut || false);
+            cnf.add_clause(vec![if value { out } else { -out }]);
+        };
+        if ins.is_empty() {
+            // Constant, whatever the gate
+            constant(cnf);
+            continue;
+        }
+
+        let negated: Vec<Lit> = ins.iter().map(|&input| -input).collect();
+        match node.gate {
+            // Ignores anything wired into it
+            Gate::G0(_) => constant(cnf),
+            Gate::G1(Gate1::Buffer) => encode_equal(cnf, out, ins[0]),
+            Gate::GN(GateN::And) => encode_and(cnf, out, &ins),
+            Gate::GN(GateN::Nand) => encode_and(cnf, -out, &ins),
+            Gate::GN(GateN::Or) => encode_and(cnf, -out, &negated),
+            Gate::GN(GateN::Nor) => encode_and(cnf, out, &negated),
+            Gate::GN(GateN::Xor) => encode_xor(cnf, out, &ins),
+            Gate::GN(GateN::Xnor) => encode_xor(cnf, -out, &ins),
+            Gate::Custom(_) if ins.len() > MAX_CUSTOM_GATE_INPUTS => {
+                return Err(format!(
+                    "{} has more than {MAX_CUSTOM_GATE_INPUTS} inputs, too many to compare",
+                    graph.node_label(id)
+                ))
+            }
+            Gate::Custom(_) => encode_table(cnf, out, &ins, &node.gate),
+        }
+    }
+    Ok(lits)
+}
+
+/// Builds a miter, which is satisfiable exactly when some input makes an output differ.
+fn check_sat(left: &Graph, right: &Graph, ports: &MatchedPorts) -> Result<Equivalence, String> {
+    let mut cnf = Cnf::new();
+    let inputs: HashMap<String, Lit> = ports
+        .inputs
+        .iter()
+        .map(|name| (name.clone(), cnf.new_var()))
+        .collect();
+    let left_lits = encode_graph(left, &mut cnf, &inputs)?;
+    let right_lits = encode_graph(right, &mut cnf, &inputs)?;
+
+    let mut output_pairs: Vec<(Lit, Lit)> = Vec::new();
+    let mut any_differs: Vec<Lit> = Vec::new();
+    for name in &ports.outputs {
+        let l = left_lits[left.find_node_by_name(name).unwrap().0];
+        let r = right_lits[right.find_node_by_name(name).unwrap().0];
+        let differs = cnf.new_var();
+        encode_xor2(&mut cnf, differs, l, r);
+        output_pairs.push((l, r));
+        any_differs.push(differs);
+    }
+    cnf.add_clause(any_differs);
+
+    let model = match cnf.solve(MAX_SAT_CONFLICTS) {
+        Solution::Satisfiable(model) => model,
+        Solution::Unsatisfiable => return Ok(Equivalence::Equivalent),
+        Solution::Unknown => return Ok(Equivalence::Unknown),
+    };
+    let value = |lit: Lit| model[lit as usize - 1];
+    let (i, &(l, r)) = output_pairs
+        .iter()
+        .enumerate()
+        .find(|(_, &(l, r))| value(l) != value(r))
+        .unwrap(); // The miter guarantees at least one
+    Ok(Equivalence::Different(Counterexample {
+        inputs: ports
+            .inputs
+            .iter()
+            .map(|name| (name.clone(), value(inputs[name])))
+            .collect(),
+        output: ports.outputs[i].clone(),
+        left: value(l),
+        right: value(r),
+    }))
+}
+
+/// Pairs up the named inputs and outputs of both circuits and checks that every output
+/// agrees under every combination of inputs.
+///
+/// Unnamed nodes with nothing wired into them are treated as the constants they evaluate to.
+pub fn check_equivalence(left: &Graph, right: &Graph) -> Result<Equivalence, String> {
+    let ports = match_ports(left, right)?;
+    for graph in [left, right] {
+        graph.ensure_loop_free()?;
+        ensure_deterministic(graph)?;
+    }
+
+    if ports.inputs.len() <= MAX_EXHAUSTIVE_INPUTS {
+        check_exhaustive(left, right, &ports)
+    } else {
+        check_sat(left, right, &ports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coords::Coords,
+        graph::{expr::Expr, plugin},
+    };
+
+    fn synthesised(source: &str) -> Graph {
+        let mut graph = Graph::new();
+        let expr: Expr = source.parse().unwrap();
+        graph.synthesise("y", &expr, &Coords::default()).unwrap();
+        graph
+    }
+
+    /// Both methods should always agree.
+    fn check_both(left: &Graph, right: &Graph) -> Equivalence {
+        let ports = match_ports(left, right).unwrap();
+        let exhaustive = check_exhaustive(left, right, &ports).unwrap();
+        let sat = check_sat(left, right, &ports).unwrap();
+        assert_eq!(
+            exhaustive == Equivalence::Equivalent,
+            sat == Equivalence::Equivalent
+        );
+        sat
+    }
+
+    #[test]
+    fn test_de_morgan() {
+        let left = synthesised("!(a & b)");
+        let right = synthesised("!a | !b");
+        assert_eq!(check_both(&left, &right), Equivalence::Equivalent);
+        assert_eq!(
+            check_equivalence(&left, &right),
+            Ok(Equivalence::Equivalent)
+        );
+    }
+
+    #[test]
+    fn test_xor_forms() {
+        let left = synthesised("a ^ b ^ c");
+        let right = synthesised("!xnor(a, b, c) & 1");
+        assert_eq!(check_both(&left, &right), Equivalence::Equivalent);
+    }
+
+    #[test]
+    fn test_counterexample() {
+        let left = synthesised("a & b");
+        let right = synthesised("a | b");
+        let Equivalence::Different(counterexample) = check_both(&left, &right) else {
+            panic!("and and or should differ");
+        };
+        assert_eq!(counterexample.output, "y");
+        assert_ne!(counterexample.left, counterexample.right);
+
+        // Whichever input made them differ, a and b must disagree
+        let values: Vec<bool> = counterexample.inputs.iter().map(|(_, v)| *v).collect();
+        assert_ne!(values[0], values[1]);
+    }
+
+    #[test]
+    fn test_wide_circuit_uses_sat() {
+        let names: Vec<String> = (0..=MAX_EXHAUSTIVE_INPUTS)
+            .map(|i| format!("x{i}"))
+            .collect();
+        let left = synthesised(&names.join(" ^ "));
+        let right = synthesised(&format!("!({})", names.join(" ^ ")));
+        let Ok(Equivalence::Different(counterexample)) = check_equivalence(&left, &right) else {
+            panic!("a function and its negation should differ");
+        };
+        assert_ne!(counterexample.left, counterexample.right);
+
+        let nested = format!("({}) ^ {}", names[1..].join(" ^ "), names[0]);
+        assert_eq!(
+            check_equivalence(&left, &synthesised(&nested)),
+            Ok(Equivalence::Equivalent)
+        );
+    }
+
+    /// Carry out of adding two `bits`-bit numbers `a` and `b`, rippling from bit to bit.
+    fn ripple_carry(bits: usize) -> String {
+        (0..bits).fold("0".to_string(), |carry, i| {
+            format!("a{i} & b{i} | (a{i} ^ b{i}) & ({carry})")
+        })
+    }
+
+    /// The same carry, looking ahead at which bits generate or propagate it.
+    fn lookahead_carry(bits: usize) -> String {
+        let terms: Vec<String> = (0..bits)
+            .map(|i| {
+                let propagate: Vec<String> =
+                    (i + 1..bits).map(|j| format!("(a{j} | b{j})")).collect();
+                let mut term = vec![format!("a{i} & b{i}")];
+                term.extend(propagate);
+                term.join(" & ")
+            })
+            .collect();
+        terms.join(" | ")
+    }
+
+    #[test]
+    fn test_wide_equivalent_circuits() {
+        // 16 inputs, built quite differently
+        let ripple = synthesised(&ripple_carry(8));
+        let lookahead = synthesised(&lookahead_carry(8));
+        assert_eq!(
+            check_equivalence(&ripple, &lookahead),
+            Ok(Equivalence::Equivalent)
+        );
+
+        // 24 inputs, where trying every combination is out of the question
+        let ripple = synthesised(&ripple_carry(12));
+        let lookahead = synthesised(&lookahead_carry(12));
+        assert_eq!(
+            check_equivalence(&ripple, &lookahead),
+            Ok(Equivalence::Equivalent)
+        );
+        let broken = synthesised(&lookahead_carry(12).replacen("a0 & b0", "a0 | b0", 1));
+        assert!(matches!(
+            check_equivalence(&ripple, &broken),
+            Ok(Equivalence::Different(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_gate() {
+        let left = synthesised("a & b | a & c | b & c");
+        let mut right = Graph::new();
+        let majority = right.add_node(&plugin::tests::majority(), &Coords { x: 2, y: 0 });
+        for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+            let input = right.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: i as i32 });
+            right.node_mut(input).name = Some(name.to_string());
+            right.add_wire(input, majority, &crate::graph::elbow::Elbow::HorzS);
+        }
+        right.node_mut(majority).name = Some("y".to_string());
+        assert_eq!(check_both(&left, &right), Equivalence::Equivalent);
+    }
+
+    #[test]
+    fn test_wire_into_constant() {
+        let left = synthesised("a & 1");
+        let mut right = synthesised("a & 1");
+        let (always, _) = right
+            .nodes()
+            .find(|(_, node)| node.gate == Gate::G0(Gate0::Always))
+            .unwrap();
+        let a = right.find_node_by_name("a").unwrap();
+        // Constants can't be wired into, but a wired node can become one
+        right.node_mut(always).gate = Gate::G1(Gate1::Buffer);
+        right.add_wire(a, always, &crate::graph::elbow::Elbow::HorzS);
+        right.node_mut(always).gate = Gate::G0(Gate0::Always);
+        assert_eq!(check_both(&left, &right), Equivalence::Equivalent);
+    }
+
+    #[test]
+    fn test_mismatched_ports() {
+        let left = synthesised("a & b");
+        let right = synthesised("a & c");
+        assert!(check_equivalence(&left, &right).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::graph::{node::NodeId, Graph};
+
+/// Cheap, deterministic source of bits for [`Gate0::Random`](crate::graph::gate::Gate0::Random).
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1)) // Zero is a fixed point
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 & 1 == 1
+    }
+}
+
+/// Settles a loop-free graph in a single pass.
+pub struct Evaluator<'g> {
+    graph: &'g Graph,
+    /// Every node comes after all of the nodes wired into it.
+    order: Vec<NodeId>,
+    inputs: Vec<Vec<NodeId>>,
+    rng: XorShift,
+}
+
+impl<'g> Evaluator<'g> {
+    /// Output of every node, indexed by [`NodeId`].
+    /// Nodes in `overrides` output the given value instead of evaluating their gate.
+    pub fn evaluate(&mut self, overrides: &HashMap<NodeId, bool>) -> Vec<bool> {
+        let mut state = vec![false; self.inputs.len()];
+        let mut values: Vec<bool> = Vec::new();
+        for &id in &self.order {
+            state[id.0] = match overrides.get(&id) {
+                Some(&value) => value,
+                None => {
+                    values.clear();
+                    values.extend(self.inputs[id.0].iter().map(|input| state[input.0]));
+                    let rng = &mut self.rng;
+                    self.graph
+                        .node(id)
+                        .gate
+                        .evaluate(&values, &mut || rng.next_bool())
+                }
+            };
+        }
+        state
+    }
+}
+
+impl Graph {
+    /// The nodes wired into each node, indexed by [`NodeId`].
+    pub fn input_lists(&self) -> Vec<Vec<NodeId>> {
+        let mut inputs = vec![Vec::new(); self.nodes.len()];
+        for wire in &self.wires {
+            inputs[wire.output.0].push(wire.input);
+        }
+        inputs
+    }
+
+    /// Anything that walks the graph from inputs to outputs needs it to be loop-free.
+    pub fn ensure_loop_free(&self) -> Result<(), String> {
+        match self.find_combinational_loops().first() {
+            Some(combinational_loop) => {
+                let coords = self.node(combinational_loop.nodes[0]).coords;
+                Err(format!("Circuit contains a combinational loop at {coords}"))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Fails if the graph contains a loop, as there is then no order to evaluate it in.
+    pub fn evaluator(&self) -> Result<Evaluator<'_>, String> {
+        self.ensure_loop_free()?;
+
+        Ok(Evaluator {
+            graph: self,
+            // Components come out of Tarjan's algorithm sinks-first
+            order: self
+                .strongly_connected_components()
+                .into_iter()
+                .rev()
+                .flatten()
+                .collect(),
+            inputs: self.input_lists(),
+            rng: XorShift::new(0x5EED),
+        })
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use crate::graph::{
+    gate::{Gate, Gate0, Gate1, GateN},
+    node::NodeId,
+    Graph,
+};
+
+/// Largest expression worth building, counting every variable, constant and gate.
+/// Shared subexpressions are written out again wherever they're used, so this can grow quickly.
+const MAX_EXPRESSION_SIZE: usize = 10_000;
+
+/// Boolean function of a node's output in terms of the graph's source nodes.
+///
+/// Displayed in function style with gates named as [`GateN`] displays them,
+/// as in `N-Gate xor(N-Gate and(a, b), !c)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Const(bool),
+    Var(String),
+    Not(Box<Expr>),
+    Gate(GateN, Vec<Expr>),
+}
+
+impl Expr {
+    /// Number of gates it would take to build the expression, counting each negation as one.
+    pub fn gate_count(&self) -> usize {
+        use Expr::*;
+        match self {
+            Const(_) | Var(_) => 0,
+            Not(expr) => 1 + expr.gate_count(),
+            Gate(_, args) => 1 + args.iter().map(Expr::gate_count).sum::<usize>(),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Expr::*;
+        match self {
+            Const(value) => write!(f, "{}", *value as u8),
+            Var(name) => write!(f, "{name}"),
+            Not(expr) => write!(f, "!{expr}"),
+            Gate(gate, args) => {
+                write!(f, "{gate}(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Not,
+    And,
+    Xor,
+    Or,
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        tokens.push(match ch {
+            '!' | '~' => Token::Not,
+            '&' => Token::And,
+            '^' => Token::Xor,
+            '|' => Token::Or,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            ch if ch.is_whitespace() => continue,
+            ch if ch.is_alphanumeric() || ch == '_' => {
+                let mut ident = ch.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    ident.push(next);
+                    chars.next();
+                }
+                Token::Ident(ident)
+            }
+            _ => return Err(format!("Unexpected `{ch}` in expression")),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first: `|`, `^`, `&`, then `!`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(format!("Expected {what}")),
+        }
+    }
+
+    /// Collects a run of the same operator into a single n-input gate.
+    fn binary(
+        &mut self,
+        op: Token,
+        gate: GateN,
+        operand: fn(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        let mut args = vec![operand(self)?];
+        while self.peek() == Some(&op) {
+            self.pos += 1;
+            args.push(operand(self)?);
+        }
+        Ok(match args.len() {
+            1 => args.pop().unwrap(),
+            _ => Expr::Gate(gate, args),
+        })
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        self.binary(Token::Or, GateN::Or, Self::xor)
+    }
+
+    fn xor(&mut self) -> Result<Expr, String> {
+        self.binary(Token::Xor, GateN::Xor, Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        self.binary(Token::And, GateN::And, Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                self.expect(Token::Close, "`)`")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) if self.peek() == Some(&Token::Open) => {
+                let gate = GateN::from_name(&ident)
+                    .ok_or_else(|| format!("`{ident}` is not an N-gate"))?;
+                self.pos += 1;
+                let mut args = vec![self.or()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                    args.push(self.or()?);
+                }
+                self.expect(Token::Close, "`)`")?;
+                Ok(Expr::Gate(gate, args))
+            }
+            Some(Token::Ident(ident)) => Ok(match ident.as_str() {
+                "0" => Expr::Const(false),
+                "1" => Expr::Const(true),
+                _ => Expr::Var(ident),
+            }),
+            _ => Err("Expected a name, `!` or `(`".to_string()),
+        }
+    }
+}
+
+/// Accepts both operators, as in `(a & b) ^ !c`, and function style, as in `xor(and(a, b), !c)`,
+/// with or without the prefix gates are displayed with.
+impl FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(&s.replace(GateN::DISPLAY_PREFIX, ""))?,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected {token:?} after expression")),
+        }
+    }
+}
+
+impl Graph {
+    /// Nodes with nothing wired into them act as the variables of an expression,
+    /// except for 0-gates which are constants.
+    fn is_source(&self, id: NodeId, inputs: &[Vec<NodeId>]) -> bool {
+        inputs[id.0].is_empty() && !matches!(self.node(id).gate, Gate::G0(_))
+    }
+
+    /// `output` and every node it depends on.
+    fn fan_in(&self, output: NodeId, inputs: &[Vec<NodeId>]) -> BTreeSet<NodeId> {
+        let mut visited: BTreeSet<NodeId> = BTreeSet::new();
+        let mut stack = vec![output];
+        while let Some(id) = stack.pop() {
+            if visited.insert(id) {
+                stack.extend(inputs[id.0].iter().copied());
+            }
+        }
+        visited
+    }
+
+    /// Every source node that `output` depends on, in ascending order.
+    pub fn source_nodes(&self, output: NodeId) -> Vec<NodeId> {
+        let inputs = self.input_lists();
+        (self.fan_in(output, &inputs).into_iter())
+            .filter(|&id| self.is_source(id, &inputs))
+            .collect()
+    }
+
+    /// Number of gates `output` depends on, each counted once however many nodes it's wired into,
+    /// unlike [`Expr::gate_count`] which counts them again wherever they're shared.
+    pub fn gate_count(&self, output: NodeId) -> usize {
+        let inputs = self.input_lists();
+        (self.fan_in(output, &inputs).into_iter())
+            .filter(|&id| matches!(self.node(id).gate, Gate::GN(_)) && !self.is_source(id, &inputs))
+            .count()
+    }
+
+    /// Size of the expression for `id` once every shared subexpression is written out,
+    /// without building it.
+    fn expression_size(
+        &self,
+        id: NodeId,
+        inputs: &[Vec<NodeId>],
+        sizes: &mut HashMap<NodeId, usize>,
+    ) -> usize {
+        if let Some(&size) = sizes.get(&id) {
+            return size;
+        }
+        let size = match self.is_source(id, inputs) {
+            true => 1,
+            false => inputs[id.0].iter().fold(1usize, |size, &input| {
+                size.saturating_add(self.expression_size(input, inputs, sizes))
+            }),
+        };
+        sizes.insert(id, size);
+        size
+    }
+
+    /// Each node's expression is only built once, however many nodes it's wired into.
+    fn build_expression(
+        &self,
+        id: NodeId,
+        inputs: &[Vec<NodeId>],
+        built: &mut HashMap<NodeId, Expr>,
+    ) -> Result<Expr, String> {
+        if let Some(expr) = built.get(&id) {
+            return Ok(expr.clone());
+        }
+        if self.is_source(id, inputs) {
+            return Ok(Expr::Var(self.node_label(id)));
+        }
+
+        let args = inputs[id.0]
+            .iter()
+            .map(|&input| self.build_expression(input, inputs, built))
+            .collect::<Result<Vec<Expr>, String>>()?;
+
+        let expr = match &self.node(id).gate {
+            Gate::G0(Gate0::Never) => Expr::Const(false),
+            Gate::G0(Gate0::Always) => Expr::Const(true),
+            Gate::G0(Gate0::Random) => {
+                return Err(format!(
+                    "{} is random and has no expression",
+                    self.node_label(id)
+                ))
+            }
+            Gate::G1(Gate1::Buffer) => args.into_iter().next().unwrap(), // Not a source, so has an input
+            Gate::GN(gate) => Expr::Gate(*gate, args),
+            Gate::Custom(_) => {
+                return Err(format!(
+                    "{} is a custom `{}` gate and has no expression",
+                    self.node_label(id),
+                    self.node(id).gate.name()
+                ))
+            }
+        };
+        built.insert(id, expr.clone());
+        Ok(expr)
+    }
+
+    /// The expression for `output` exactly as the graph computes it.
+    pub fn expression(&self, output: NodeId) -> Result<Expr, String> {
+        self.ensure_loop_free()?;
+        let inputs = self.input_lists();
+        if self.expression_size(output, &inputs, &mut HashMap::new()) > MAX_EXPRESSION_SIZE {
+            return Err(format!(
+                "The expression for {} has more than {MAX_EXPRESSION_SIZE} terms, too many to show",
+                self.node_label(output)
+            ));
+        }
+        self.build_expression(output, &inputs, &mut HashMap::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coords::Coords, graph::elbow::Elbow};
+
+    #[test]
+    fn test_expression() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 0 });
+        let b = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 1 });
+        let one = graph.add_node(&Gate::G0(Gate0::Always), &Coords { x: 0, y: 2 });
+        let and = graph.add_node(&Gate::GN(GateN::And), &Coords { x: 1, y: 0 });
+        let buffer = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 2, y: 0 });
+        let xor = graph.add_node(&Gate::GN(GateN::Xor), &Coords { x: 3, y: 0 });
+        graph.node_mut(a).name = Some("a".to_string());
+        graph.node_mut(b).name = Some("b".to_string());
+        graph.add_wire(a, and, &Elbow::HorzS);
+        graph.add_wire(b, and, &Elbow::HorzS);
+        graph.add_wire(and, buffer, &Elbow::HorzS);
+        graph.add_wire(buffer, xor, &Elbow::HorzS);
+        graph.add_wire(one, xor, &Elbow::HorzS);
+
+        let expr = graph.expression(xor).unwrap();
+        assert_eq!(expr.to_string(), "N-Gate xor(N-Gate and(a, b), 1)");
+        assert_eq!(expr.gate_count(), 2);
+        assert_eq!(graph.gate_count(xor), 2);
+        assert_eq!(graph.source_nodes(xor), vec![a, b]);
+    }
+
+    #[test]
+    fn test_gate_count_shared() {
+        // Both inputs of the or come from the same nand, which is only one gate
+        let mut graph = Graph::new();
+        let a = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 0 });
+        let b = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 1 });
+        let nand = graph.add_node(&Gate::GN(GateN::Nand), &Coords { x: 1, y: 0 });
+        let nor = graph.add_node(&Gate::GN(GateN::Nor), &Coords { x: 2, y: 0 });
+        let or = graph.add_node(&Gate::GN(GateN::Or), &Coords { x: 3, y: 0 });
+        graph.add_wire(a, nand, &Elbow::HorzS);
+        graph.add_wire(b, nand, &Elbow::HorzS);
+        graph.add_wire(nand, nor, &Elbow::HorzS);
+        graph.add_wire(nand, or, &Elbow::HorzS);
+        graph.add_wire(nor, or, &Elbow::HorzS);
+
+        assert_eq!(graph.expression(or).unwrap().gate_count(), 4);
+        assert_eq!(graph.gate_count(or), 3);
+    }
+
+    #[test]
+    fn test_parse() {
+        let expr: Expr = "(a & b) ^ !c".parse().unwrap();
+        assert_eq!(expr.to_string(), "N-Gate xor(N-Gate and(a, b), !c)");
+
+        let expr: Expr = "a | b & c | 1".parse().unwrap();
+        assert_eq!(expr.to_string(), "N-Gate or(a, N-Gate and(b, c), 1)");
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let source = "N-Gate nor(N-Gate xnor(a, b), !N-Gate and(c, 0))";
+        let expr: Expr = source.parse().unwrap();
+        assert_eq!(expr.to_string(), source);
+        assert_eq!("nor(xnor(a, b), !and(c, 0))".parse(), Ok(expr));
+    }
+
+    #[test]
+    fn test_shared_subexpressions() {
+        // Each level uses the one before twice, doubling the size of the expression
+        let mut graph = Graph::new();
+        let mut prev = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 0 });
+        graph.node_mut(prev).name = Some("a".to_string());
+        let mut levels = Vec::new();
+        for x in 1..=20 {
+            let and = graph.add_node(&Gate::GN(GateN::And), &Coords { x, y: 0 });
+            for y in [1, 2] {
+                let buffer = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x, y });
+                graph.add_wire(prev, buffer, &Elbow::HorzS);
+                graph.add_wire(buffer, and, &Elbow::HorzS);
+            }
+            levels.push(and);
+            prev = and;
+        }
+
+        let expr = graph.expression(levels[1]).unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "N-Gate and(N-Gate and(a, a), N-Gate and(a, a))"
+        );
+        assert!(graph
+            .expression(prev)
+            .unwrap_err()
+            .contains("too many to show"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("a &".parse::<Expr>().is_err());
+        assert!("(a | b".parse::<Expr>().is_err());
+        assert!("a b".parse::<Expr>().is_err());
+        assert!("buffer(a)".parse::<Expr>().is_err());
+        assert!("a + b".parse::<Expr>().is_err());
+    }
+}
@@ -0,0 +1,424 @@
+#[cfg(feature = "raylib")]
+use raylib::prelude::*;
+
+#[cfg(feature = "raylib")]
+use crate::coords::Coords;
+use crate::{
+    common_traits::Scrollable,
+    graph::plugin::{self, PluginId},
+};
+use std::fmt::{self, Formatter};
+
+pub trait GateIn {
+    const MAX_INPUTS: usize;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gate0 {
+    Never,
+    Always,
+    Random,
+}
+
+impl Scrollable for Gate0 {
+    fn next(&self) -> Self {
+        use Gate0::*;
+        match *self {
+            Never => Always,
+            Always => Random,
+            Random => Never,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        use Gate0::*;
+        match *self {
+            Never => Random,
+            Always => Never,
+            Random => Always,
+        }
+    }
+}
+
+impl Gate0 {
+    pub const ALL: [Gate0; 3] = [Gate0::Never, Gate0::Always, Gate0::Random];
+}
+
+impl GateIn for Gate0 {
+    const MAX_INPUTS: usize = 0;
+}
+
+impl fmt::Display for Gate0 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        use Gate0::*;
+        write!(
+            f,
+            "0-Gate {}",
+            match *self {
+                Never => "false",
+                Always => "true",
+                Random => "rand",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gate1 {
+    Buffer,
+}
+
+impl Scrollable for Gate1 {
+    fn next(&self) -> Self {
+        use Gate1::*;
+        match *self {
+            Buffer => Buffer,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        use Gate1::*;
+        match *self {
+            Buffer => Buffer,
+        }
+    }
+}
+
+impl GateIn for Gate1 {
+    const MAX_INPUTS: usize = 1;
+}
+
+impl fmt::Display for Gate1 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        use Gate1::*;
+        write!(
+            f,
+            "1-Gate {}",
+            match *self {
+                Buffer => "buffer",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateN {
+    And,
+    Nand,
+    Or,
+    Nor,
+    Xor,
+    Xnor,
+}
+
+impl Scrollable for GateN {
+    fn next(&self) -> Self {
+        use GateN::*;
+        match *self {
+            And => Nand,
+            Nand => Or,
+            Or => Nor,
+            Nor => Xor,
+            Xor => Xnor,
+            Xnor => And,
+        }
+    }
+
+    fn prev(&self) -> Self {
+        use GateN::*;
+        match *self {
+            And => Xnor,
+            Nand => And,
+            Or => Nand,
+            Nor => Or,
+            Xor => Nor,
+            Xnor => Xor,
+        }
+    }
+}
+
+impl GateN {
+    /// Put before the name when displayed, as in `N-Gate and`.
+    pub const DISPLAY_PREFIX: &'static str = "N-Gate ";
+
+    pub const ALL: [GateN; 6] = [
+        GateN::And,
+        GateN::Nand,
+        GateN::Or,
+        GateN::Nor,
+        GateN::Xor,
+        GateN::Xnor,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        use GateN::*;
+        match *self {
+            And => "and",
+            Nand => "nand",
+            Or => "or",
+            Nor => "nor",
+            Xor => "xor",
+            Xnor => "xnor",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|gate| gate.name() == name)
+    }
+
+    pub fn evaluate(&self, inputs: &[bool]) -> bool {
+        use GateN::*;
+        let high = inputs.iter().filter(|&&input| input).count();
+        match *self {
+            And => !inputs.is_empty() && high == inputs.len(),
+            Nand => inputs.is_empty() || high != inputs.len(),
+            Or => high > 0,
+            Nor => high == 0,
+            Xor => high % 2 == 1,
+            Xnor => high % 2 == 0,
+        }
+    }
+}
+
+impl GateIn for GateN {
+    const MAX_INPUTS: usize = usize::MAX;
+}
+
+impl fmt::Display for GateN {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", Self::DISPLAY_PREFIX, self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Gate {
+    G0(Gate0),
+    G1(Gate1),
+    GN(GateN),
+    /// A registered [`GatePlugin`](plugin::GatePlugin)
+    Custom(PluginId),
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        use Gate::*;
+
+        match self {
+            G0(gate_0) => gate_0.fmt(f),
+            G1(gate_1) => gate_1.fmt(f),
+            GN(gate_n) => gate_n.fmt(f),
+            Custom(id) => write!(f, "Custom {}", id.name()),
+        }
+    }
+}
+
+/// Walks every gate in the order of [`Gate::all`], crossing from one family to the next.
+impl Scrollable for Gate {
+    fn next(&self) -> Self {
+        let gates = Gate::all();
+        gates[(self.position(&gates) + 1) % gates.len()]
+    }
+
+    fn prev(&self) -> Self {
+        let gates = Gate::all();
+        gates[(self.position(&gates) + gates.len() - 1) % gates.len()]
+    }
+}
+
+/// Draws plugins onto the screen around a node.
+#[cfg(feature = "raylib")]
+struct RaylibCanvas<'a, D: RaylibDraw> {
+    d: &'a mut D,
+    center: Vector2,
+    color: Color,
+}
+
+#[cfg(feature = "raylib")]
+impl<D: RaylibDraw> plugin::Canvas for RaylibCanvas<'_, D> {
+    fn circle(&mut self, (x, y): (f32, f32), radius: f32) {
+        let center = self.center + Vector2::new(x, y);
+        self.d.draw_circle_v(center, radius, self.color);
+    }
+
+    fn circle_outline(&mut self, (x, y): (f32, f32), radius: f32) {
+        let center = self.center + Vector2::new(x, y);
+        self.d
+            .draw_circle_lines(center.x as i32, center.y as i32, radius, self.color);
+    }
+
+    fn line(&mut self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) {
+        let start = self.center + Vector2::new(x1, y1);
+        let end = self.center + Vector2::new(x2, y2);
+        self.d.draw_line_v(start, end, self.color);
+    }
+}
+
+#[cfg(feature = "raylib")]
+impl Gate {
+    pub fn draw_v(&self, d: &mut impl RaylibDraw, center: &Vector2, color: Color) {
+        match self {
+            Gate::Custom(id) => id.draw(&mut RaylibCanvas {
+                d,
+                center: *center,
+                color,
+            }),
+            _ => d.draw_circle_v(center, Coords::GRID_SIZE / 2.0, color),
+        }
+    }
+
+    pub fn draw(&self, d: &mut impl RaylibDraw, coords: &Coords, color: Color) {
+        self.draw_v(d, &coords.into_position(), color);
+    }
+}
+
+impl Gate {
+    /// Stable identifier for the gate, used in save files.
+    pub fn name(&self) -> &'static str {
+        use Gate::*;
+        match self {
+            G0(Gate0::Never) => "never",
+            G0(Gate0::Always) => "always",
+            G0(Gate0::Random) => "random",
+            G1(Gate1::Buffer) => "buffer",
+            GN(gate_n) => gate_n.name(),
+            Custom(id) => id.name(),
+        }
+    }
+
+    /// Every gate, built-in families first and then plugins in the order of [`plugin::all`].
+    pub fn all() -> Vec<Gate> {
+        let mut gates: Vec<Gate> = Gate0::ALL.into_iter().map(Gate::G0).collect();
+        gates.push(Gate::G1(Gate1::Buffer));
+        gates.extend(GateN::ALL.into_iter().map(Gate::GN));
+        gates.extend(plugin::all().into_iter().map(Gate::Custom));
+        gates
+    }
+
+    fn position(&self, gates: &[Gate]) -> usize {
+        gates
+            .iter()
+            .position(|gate| gate == self)
+            .expect("Every gate is listed by Gate::all")
+    }
+
+    /// Next gate of the same family, such as And to Nand, wrapping around within it.
+    pub fn next_in_family(&self) -> Self {
+        use Gate::*;
+        match self {
+            G0(gate_0) => G0(gate_0.next()),
+            G1(gate_1) => G1(gate_1.next()),
+            GN(gate_n) => GN(gate_n.next()),
+            Custom(id) => Custom(id.next()),
+        }
+    }
+
+    pub fn prev_in_family(&self) -> Self {
+        use Gate::*;
+        match self {
+            G0(gate_0) => G0(gate_0.prev()),
+            G1(gate_1) => G1(gate_1.prev()),
+            GN(gate_n) => GN(gate_n.prev()),
+            Custom(id) => Custom(id.prev()),
+        }
+    }
+
+    /// Short symbol for the gate, drawn inside it in exported images and the palette.
+    pub fn glyph(&self) -> &'static str {
+        use Gate::*;
+        match self {
+            G0(Gate0::Never) => "0",
+            G0(Gate0::Always) => "1",
+            G0(Gate0::Random) => "?",
+            G1(Gate1::Buffer) => ">",
+            GN(GateN::And) => "&",
+            GN(GateN::Nand) => "!&",
+            GN(GateN::Or) => "|",
+            GN(GateN::Nor) => "!|",
+            GN(GateN::Xor) => "^",
+            GN(GateN::Xnor) => "!^",
+            Custom(id) => id.glyph(),
+        }
+    }
+
+    /// Most inputs the gate makes use of.
+    pub fn max_inputs(&self) -> usize {
+        use Gate::*;
+        match self {
+            G0(_) => Gate0::MAX_INPUTS,
+            G1(_) => Gate1::MAX_INPUTS,
+            GN(_) => GateN::MAX_INPUTS,
+            Custom(id) => id.max_inputs(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        use Gate::*;
+        match name {
+            "never" => Some(G0(Gate0::Never)),
+            "always" => Some(G0(Gate0::Always)),
+            "random" => Some(G0(Gate0::Random)),
+            "buffer" => Some(G1(Gate1::Buffer)),
+            _ => GateN::from_name(name)
+                .map(GN)
+                .or_else(|| plugin::find(name).map(Custom)),
+        }
+    }
+
+    /// Output of the gate given the outputs of every node wired into it.
+    /// Inputs beyond [`Gate::max_inputs`] are ignored.
+    ///
+    /// `random` is only called by [`Gate0::Random`].
+    pub fn evaluate(&self, inputs: &[bool], random: &mut impl FnMut() -> bool) -> bool {
+        use Gate::*;
+        let inputs = &inputs[..inputs.len().min(self.max_inputs())];
+        match self {
+            G0(Gate0::Never) => false,
+            G0(Gate0::Always) => true,
+            G0(Gate0::Random) => random(),
+            G1(Gate1::Buffer) => inputs.first().copied().unwrap_or(false),
+            GN(gate_n) => gate_n.evaluate(inputs),
+            Custom(id) => id.evaluate(inputs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_across_families() {
+        let buffer = Gate::G1(Gate1::Buffer);
+        assert_eq!(buffer.next(), Gate::GN(GateN::And));
+        assert_eq!(buffer.prev(), Gate::G0(Gate0::Random));
+        assert_eq!(Gate::G0(Gate0::Never).next(), Gate::G0(Gate0::Always));
+
+        // Scrolling all the way round visits every gate once, plugins included.
+        // Registered up front so another test can't add it partway round.
+        let majority = plugin::tests::majority();
+        let (sum, _) = plugin::tests::half_adder();
+        crate::plugins::register_all().unwrap();
+        let mut gate = buffer;
+        let mut seen = Vec::new();
+        loop {
+            seen.push(gate);
+            gate.incr();
+            if gate == buffer {
+                break;
+            }
+        }
+        assert_eq!(seen.len(), Gate::all().len());
+        assert!(seen.contains(&majority));
+        assert!(seen.contains(&sum));
+    }
+
+    #[test]
+    fn test_scroll_within_family() {
+        let and = Gate::GN(GateN::And);
+        assert_eq!(and.next_in_family(), Gate::GN(GateN::Nand));
+        assert_eq!(and.prev_in_family(), Gate::GN(GateN::Xnor));
+        let buffer = Gate::G1(Gate1::Buffer);
+        assert_eq!(buffer.next_in_family(), buffer);
+    }
+}
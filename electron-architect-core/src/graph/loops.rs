@@ -0,0 +1,189 @@
+use crate::graph::{node::NodeId, wire::WireId, Graph};
+
+/// A cycle of nodes with no storage element to break it.
+pub struct CombinationalLoop {
+    /// Every node in the strongly-connected component, in ascending order.
+    pub nodes: Vec<NodeId>,
+    /// Every wire whose input and output are both part of the loop.
+    pub wires: Vec<WireId>,
+}
+
+impl Graph {
+    /// Tarjan's algorithm, iterative so that long chains can't overflow the stack.
+    /// Components are returned in reverse topological order.
+    pub(super) fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let n = self.nodes.len();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for wire in &self.wires {
+            adjacency[wire.input.0].push(wire.output.0);
+        }
+
+        let mut index: Vec<Option<usize>> = vec![None; n];
+        let mut lowlink: Vec<usize> = vec![0; n];
+        let mut on_stack: Vec<bool> = vec![false; n];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut next_index: usize = 0;
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+
+        for root in 0..n {
+            if index[root].is_some() {
+                continue;
+            }
+
+            // (node, position of the next edge to explore)
+            let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+            index[root] = Some(next_index);
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(frame) = call_stack.last_mut() {
+                let v = frame.0;
+                if let Some(&w) = adjacency[v].get(frame.1) {
+                    frame.1 += 1;
+                    match index[w] {
+                        None => {
+                            index[w] = Some(next_index);
+                            lowlink[w] = next_index;
+                            next_index += 1;
+                            stack.push(w);
+                            on_stack[w] = true;
+                            call_stack.push((w, 0));
+                        }
+                        Some(w_index) if on_stack[w] => {
+                            lowlink[v] = lowlink[v].min(w_index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[v]);
+                }
+
+                if Some(lowlink[v]) == index[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap(); // v is always still on the stack
+                        on_stack[w] = false;
+                        component.push(NodeId(w));
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Finds every feedback loop in the graph.
+    ///
+    /// A loop is any strongly-connected component of more than one node,
+    /// or a single node wired into itself.
+    pub fn find_combinational_loops(&self) -> Vec<CombinationalLoop> {
+        let mut component_of: Vec<usize> = vec![0; self.nodes.len()];
+        let components = self.strongly_connected_components();
+        for (i, component) in components.iter().enumerate() {
+            for id in component {
+                component_of[id.0] = i;
+            }
+        }
+
+        let mut loops: Vec<CombinationalLoop> = components
+            .into_iter()
+            .map(|nodes| CombinationalLoop {
+                nodes,
+                wires: Vec::new(),
+            })
+            .collect();
+
+        for (i, wire) in self.wires.iter().enumerate() {
+            let component = component_of[wire.input.0];
+            if component == component_of[wire.output.0] {
+                loops[component].wires.push(WireId(i));
+            }
+        }
+
+        // A lone node is only a loop if it feeds itself
+        loops.retain(|l| !l.wires.is_empty());
+        loops.sort_by_key(|l| l.nodes[0]);
+        loops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coords::Coords,
+        graph::{
+            elbow::Elbow,
+            gate::{Gate, GateN},
+        },
+    };
+
+    const TEST_GATE: Gate = Gate::GN(GateN::Or);
+
+    fn chain(graph: &mut Graph, len: i32) -> Vec<NodeId> {
+        let ids: Vec<NodeId> = (0..len)
+            .map(|x| graph.add_node(&TEST_GATE, &Coords { x, y: 0 }))
+            .collect();
+        for pair in ids.windows(2) {
+            graph.add_wire(pair[0], pair[1], &Elbow::HorzS);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_acyclic() {
+        let mut graph = Graph::new();
+        chain(&mut graph, 5);
+        assert!(graph.find_combinational_loops().is_empty());
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let mut graph = Graph::new();
+        let ids = chain(&mut graph, 3);
+        graph.add_wire(ids[1], ids[1], &Elbow::HorzS);
+
+        let loops = graph.find_combinational_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].nodes, vec![ids[1]]);
+        assert_eq!(loops[0].wires, vec![WireId(2)]);
+    }
+
+    #[test]
+    fn test_feedback_loop() {
+        let mut graph = Graph::new();
+        let ids = chain(&mut graph, 5);
+        let feedback = graph.add_wire(ids[3], ids[1], &Elbow::VertS);
+
+        let loops = graph.find_combinational_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].nodes, vec![ids[1], ids[2], ids[3]]);
+        assert_eq!(loops[0].wires, vec![WireId(1), WireId(2), feedback]);
+    }
+
+    #[test]
+    fn test_separate_loops() {
+        let mut graph = Graph::new();
+        let a = chain(&mut graph, 2);
+        let b = chain(&mut graph, 2);
+        graph.add_wire(a[1], a[0], &Elbow::HorzS);
+        graph.add_wire(b[1], b[0], &Elbow::HorzS);
+        graph.add_wire(a[1], b[0], &Elbow::HorzS); // Joins them, but only one way
+
+        let loops = graph.find_combinational_loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].nodes, a);
+        assert_eq!(loops[1].nodes, b);
+    }
+}
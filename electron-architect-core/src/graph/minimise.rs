@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+use crate::graph::{expr::Expr, gate::GateN, node::NodeId, Graph};
+
+/// A product term. Bits set in `dont_care` are absent from the term,
+/// the rest must equal the matching bit of `bits`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Implicant {
+    bits: u32,
+    dont_care: u32,
+}
+
+impl Implicant {
+    fn covers(&self, minterm: u32) -> bool {
+        minterm & !self.dont_care == self.bits
+    }
+}
+
+/// Quine–McCluskey: merge terms differing in a single bit until nothing merges.
+fn prime_implicants(num_vars: usize, minterms: &[u32]) -> Vec<Implicant> {
+    let mut current: BTreeSet<Implicant> = minterms
+        .iter()
+        .map(|&bits| Implicant { bits, dont_care: 0 })
+        .collect();
+    let mut primes: Vec<Implicant> = Vec::new();
+
+    while !current.is_empty() {
+        let mut merged: BTreeSet<Implicant> = BTreeSet::new();
+        let mut next: BTreeSet<Implicant> = BTreeSet::new();
+
+        for term in &current {
+            for bit in (0..num_vars).map(|i| 1u32 << i) {
+                if (term.bits | term.dont_care) & bit != 0 {
+                    continue;
+                }
+                let partner = Implicant {
+                    bits: term.bits | bit,
+                    dont_care: term.dont_care,
+                };
+                if current.contains(&partner) {
+                    merged.insert(*term);
+                    merged.insert(partner);
+                    next.insert(Implicant {
+                        bits: term.bits,
+                        dont_care: term.dont_care | bit,
+                    });
+                }
+            }
+        }
+
+        primes.extend(current.difference(&merged));
+        current = next;
+    }
+
+    primes
+}
+
+/// Takes every essential prime implicant, then greedily whichever covers the most of what remains.
+/// Not guaranteed minimal when there are cyclic covers, but close.
+fn select_cover(primes: &[Implicant], minterms: &[u32]) -> Vec<Implicant> {
+    let mut chosen: BTreeSet<Implicant> = BTreeSet::new();
+    for &minterm in minterms {
+        let mut covering = primes.iter().filter(|prime| prime.covers(minterm));
+        if let (Some(&only), None) = (covering.next(), covering.next()) {
+            chosen.insert(only);
+        }
+    }
+
+    let mut uncovered: Vec<u32> = minterms
+        .iter()
+        .copied()
+        .filter(|&minterm| !chosen.iter().any(|prime| prime.covers(minterm)))
+        .collect();
+
+    while !uncovered.is_empty() {
+        let best = *primes
+            .iter()
+            .max_by_key(|prime| {
+                let covered = uncovered.iter().filter(|&&m| prime.covers(m)).count();
+                (covered, prime.dont_care.count_ones())
+            })
+            .unwrap(); // Every minterm is covered by at least one prime
+        uncovered.retain(|&minterm| !best.covers(minterm));
+        chosen.insert(best);
+    }
+
+    chosen.into_iter().collect()
+}
+
+/// Minimal sum-of-products for the function that is true for exactly `minterms`.
+///
+/// The first variable is the most significant bit of each minterm, matching truth table row order.
+pub fn minimise(variables: &[String], minterms: &[u32]) -> Expr {
+    let num_vars = variables.len();
+    let cover = select_cover(&prime_implicants(num_vars, minterms), minterms);
+
+    let mut terms: Vec<Expr> = cover
+        .iter()
+        .map(|implicant| {
+            let mut literals: Vec<Expr> = variables
+                .iter()
+                .enumerate()
+                .filter_map(|(i, name)| {
+                    let bit = 1u32 << (num_vars - 1 - i);
+                    if implicant.dont_care & bit != 0 {
+                        None
+                    } else if implicant.bits & bit != 0 {
+                        Some(Expr::Var(name.clone()))
+                    } else {
+                        Some(Expr::Not(Box::new(Expr::Var(name.clone()))))
+                    }
+                })
+                .collect();
+            match literals.len() {
+                0 => Expr::Const(true),
+                1 => literals.pop().unwrap(),
+                _ => Expr::Gate(GateN::And, literals),
+            }
+        })
+        .collect();
+
+    match terms.len() {
+        0 => Expr::Const(false),
+        1 => terms.pop().unwrap(),
+        _ => Expr::Gate(GateN::Or, terms),
+    }
+}
+
+impl Graph {
+    /// The simplest sum-of-products with the same truth table as `output`.
+    pub fn minimised_expression(&self, output: NodeId) -> Result<Expr, String> {
+        let sources = self.source_nodes(output);
+        let table = self.truth_table(&sources, &[output])?;
+        Ok(minimise(&table.input_names, &table.minterms(0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(minimise(&vars("a b"), &[]), Expr::Const(false));
+        assert_eq!(minimise(&vars("a b"), &[0, 1, 2, 3]), Expr::Const(true));
+    }
+
+    #[test]
+    fn test_redundant_variable() {
+        // a & b | a & !b
+        assert_eq!(minimise(&vars("a b"), &[2, 3]).to_string(), "a");
+    }
+
+    #[test]
+    fn test_xor_does_not_simplify() {
+        assert_eq!(
+            minimise(&vars("a b"), &[1, 2]).to_string(),
+            "N-Gate or(N-Gate and(!a, b), N-Gate and(a, !b))"
+        );
+    }
+
+    #[test]
+    fn test_majority() {
+        assert_eq!(
+            minimise(&vars("a b c"), &[3, 5, 6, 7]).to_string(),
+            "N-Gate or(N-Gate and(b, c), N-Gate and(a, c), N-Gate and(a, b))"
+        );
+    }
+}
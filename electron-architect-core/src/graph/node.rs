@@ -0,0 +1,59 @@
+use crate::{coords::Coords, graph::gate::Gate};
+
+#[cfg(feature = "raylib")]
+use raylib::prelude::*;
+
+/// Index of a [`Node`] within the [`Graph`](crate::graph::Graph) that owns it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct NodeId(pub usize);
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "n{}", self.0)
+    }
+}
+
+pub struct Node {
+    pub gate: Gate,
+    pub coords: Coords,
+    /// Label used to refer to the node from console commands.
+    pub name: Option<String>,
+}
+
+impl Node {
+    pub fn new(gate: &Gate, coords: &Coords) -> Self {
+        Self {
+            gate: *gate,
+            coords: *coords,
+            name: None,
+        }
+    }
+
+    pub const NAME_FONT_SIZE: i32 = 8;
+
+    /// Names are a single word, so they can be saved and typed at the prompt.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && !name.contains(char::is_whitespace)
+    }
+
+    #[cfg(feature = "raylib")]
+    pub fn draw(&self, d: &mut impl RaylibDraw) {
+        self.gate.draw(d, &self.coords, Color::GRAY);
+        if let Some(name) = &self.name {
+            let pos = self.coords.into_position();
+            d.draw_text(
+                name,
+                (pos.x + Coords::GRID_RADIUS) as i32,
+                (pos.y - Coords::GRID_SIZE) as i32,
+                Self::NAME_FONT_SIZE,
+                Color::LIGHTGRAY,
+            );
+        }
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Node(gate: {}, coords: {})", self.gate, self.coords,)
+    }
+}
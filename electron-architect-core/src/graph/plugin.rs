@@ -0,0 +1,315 @@
+//! Gate types defined outside this crate, such as wide primitives compiled into the editor.
+//!
+//! Plugins are registered once at startup and are then referred to by [`PluginId`],
+//! which is what [`Gate::Custom`](crate::graph::gate::Gate::Custom) holds.
+
+use std::sync::RwLock;
+
+use crate::{coords::Coords, graph::gate::Gate};
+
+/// Somewhere a plugin can draw its gate, in world units relative to the centre of the node
+/// and in whatever colour the node is being drawn.
+pub trait Canvas {
+    fn circle(&mut self, center: (f32, f32), radius: f32);
+    fn circle_outline(&mut self, center: (f32, f32), radius: f32);
+    fn line(&mut self, start: (f32, f32), end: (f32, f32));
+}
+
+/// A user-defined gate.
+///
+/// Each output becomes a gate of its own, named by [`GatePlugin::output_name`], so a block with
+/// several outputs, such as a 4-bit adder, is placed as one node per output bit, each wired from the
+/// same inputs.
+pub trait GatePlugin: Send + Sync {
+    /// Identifier used in save files and scripts, which can't be the name of a built-in gate.
+    fn name(&self) -> &'static str;
+
+    /// Short symbol drawn inside the gate in exported images.
+    fn glyph(&self) -> &'static str;
+
+    /// Most inputs the gate uses. Any beyond this are ignored.
+    fn max_inputs(&self) -> usize;
+
+    /// Number of outputs, each of which is a separate gate.
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    /// Identifier for one of the outputs, used in place of [`GatePlugin::name`] for that gate.
+    /// Must be overridden with a distinct name for each output when there's more than one.
+    fn output_name(&self, _output: usize) -> &'static str {
+        self.name()
+    }
+
+    /// Where the plugin's gates are listed among the other plugins when scrolling through gates,
+    /// lowest first, after every built-in gate. Ties keep the order they were registered in.
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Value of `output` given the outputs of every node wired in, in the order the wires were added.
+    fn evaluate(&self, inputs: &[bool], output: usize) -> bool;
+
+    /// Defaults to the usual circle, ringed to set it apart from the built-in gates.
+    fn draw(&self, canvas: &mut dyn Canvas) {
+        canvas.circle((0.0, 0.0), Coords::GRID_RADIUS);
+        canvas.circle_outline((0.0, 0.0), Coords::GRID_RADIUS + 2.0);
+    }
+}
+
+/// One output of a registered plugin, in the order they were registered.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct PluginId(usize);
+
+#[derive(Clone, Copy)]
+struct Entry {
+    plugin: &'static dyn GatePlugin,
+    output: usize,
+}
+
+static REGISTRY: RwLock<Vec<Entry>> = RwLock::new(Vec::new());
+
+/// Makes each output of `plugin` available as a gate, returning the id of the first.
+/// Registering the same plugin again returns the same id.
+pub fn register(plugin: &'static dyn GatePlugin) -> Result<PluginId, String> {
+    let names: Vec<&str> = (0..plugin.outputs())
+        .map(|output| plugin.output_name(output))
+        .collect();
+    if names.is_empty() {
+        return Err(format!(
+            "The gate plugin `{}` has no outputs",
+            plugin.name()
+        ));
+    }
+    for (i, name) in names.iter().enumerate() {
+        // Checked before locking, as looking a gate up by name reads the registry
+        if Gate::from_name(name).is_some_and(|gate| !matches!(gate, Gate::Custom(_))) {
+            return Err(format!("`{name}` is already a built-in gate"));
+        }
+        if names[..i].contains(name) {
+            return Err(format!(
+                "The gate plugin `{}` has two outputs named `{name}`",
+                plugin.name()
+            ));
+        }
+    }
+
+    let mut registry = REGISTRY.write().unwrap();
+    if let Some(i) = registry
+        .iter()
+        .position(|entry| names.contains(&entry.plugin.output_name(entry.output)))
+    {
+        let entry = registry[i];
+        return match std::ptr::addr_eq(entry.plugin, plugin) {
+            true => Ok(PluginId(i - entry.output)),
+            false => Err(format!(
+                "A gate plugin named `{}` is already registered",
+                entry.plugin.output_name(entry.output)
+            )),
+        };
+    }
+    let first = registry.len();
+    registry.extend((0..names.len()).map(|output| Entry { plugin, output }));
+    Ok(PluginId(first))
+}
+
+pub fn find(name: &str) -> Option<PluginId> {
+    let registry = REGISTRY.read().unwrap();
+    registry
+        .iter()
+        .position(|entry| entry.plugin.output_name(entry.output) == name)
+        .map(PluginId)
+}
+
+/// Every registered plugin output, by [`GatePlugin::order`] and then in the order they were registered.
+pub fn all() -> Vec<PluginId> {
+    let registry = REGISTRY.read().unwrap();
+    let mut ids: Vec<PluginId> = (0..registry.len()).map(PluginId).collect();
+    // Stable, so ties stay in registration order
+    ids.sort_by_key(|id| registry[id.0].plugin.order());
+    ids
+}
+
+impl PluginId {
+    fn entry(self) -> Entry {
+        REGISTRY.read().unwrap()[self.0]
+    }
+
+    pub fn name(self) -> &'static str {
+        let entry = self.entry();
+        entry.plugin.output_name(entry.output)
+    }
+
+    pub fn glyph(self) -> &'static str {
+        self.entry().plugin.glyph()
+    }
+
+    pub fn max_inputs(self) -> usize {
+        self.entry().plugin.max_inputs()
+    }
+
+    pub fn evaluate(self, inputs: &[bool]) -> bool {
+        let entry = self.entry();
+        entry.plugin.evaluate(inputs, entry.output)
+    }
+
+    pub fn draw(self, canvas: &mut dyn Canvas) {
+        self.entry().plugin.draw(canvas)
+    }
+
+    fn position(self, ids: &[PluginId]) -> usize {
+        ids.iter()
+            .position(|&id| id == self)
+            .expect("Every plugin is listed by plugin::all")
+    }
+
+    /// Next plugin in the order of [`all`], wrapping around.
+    pub fn next(self) -> Self {
+        let ids = all();
+        ids[(self.position(&ids) + 1) % ids.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let ids = all();
+        ids[(self.position(&ids) + ids.len() - 1) % ids.len()]
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// True when most of its three inputs are, which is the carry out of a full adder.
+    pub(crate) struct Majority;
+
+    impl GatePlugin for Majority {
+        fn name(&self) -> &'static str {
+            "test_majority"
+        }
+
+        fn glyph(&self) -> &'static str {
+            "M"
+        }
+
+        fn max_inputs(&self) -> usize {
+            3
+        }
+
+        fn evaluate(&self, inputs: &[bool], _: usize) -> bool {
+            inputs.iter().take(3).filter(|&&input| input).count() >= 2
+        }
+    }
+
+    pub(crate) fn majority() -> Gate {
+        Gate::Custom(register(&Majority).unwrap())
+    }
+
+    struct Impostor(&'static str);
+
+    impl GatePlugin for Impostor {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn glyph(&self) -> &'static str {
+            "?"
+        }
+
+        fn max_inputs(&self) -> usize {
+            0
+        }
+
+        fn evaluate(&self, _: &[bool], _: usize) -> bool {
+            false
+        }
+    }
+
+    /// Sum and carry of two bits, listed before other plugins.
+    pub(crate) struct HalfAdder;
+
+    impl GatePlugin for HalfAdder {
+        fn name(&self) -> &'static str {
+            "test_half_adder"
+        }
+
+        fn glyph(&self) -> &'static str {
+            "+"
+        }
+
+        fn max_inputs(&self) -> usize {
+            2
+        }
+
+        fn outputs(&self) -> usize {
+            2
+        }
+
+        fn output_name(&self, output: usize) -> &'static str {
+            ["test_sum", "test_carry"][output]
+        }
+
+        fn order(&self) -> i32 {
+            -1
+        }
+
+        fn evaluate(&self, inputs: &[bool], output: usize) -> bool {
+            let input = |i: usize| inputs.get(i).copied().unwrap_or(false);
+            let (a, b) = (input(0), input(1));
+            match output {
+                0 => a ^ b,
+                _ => a && b,
+            }
+        }
+    }
+
+    /// The sum and carry gates of [`HalfAdder`].
+    pub(crate) fn half_adder() -> (Gate, Gate) {
+        let sum = register(&HalfAdder).unwrap();
+        (Gate::Custom(sum), Gate::Custom(PluginId(sum.0 + 1)))
+    }
+
+    #[test]
+    fn test_register() {
+        let gate = majority();
+        assert_eq!(gate, majority());
+        assert_eq!(Gate::from_name("test_majority"), Some(gate));
+        assert_eq!(gate.name(), "test_majority");
+        assert!(gate.evaluate(&[true, false, true], &mut || false));
+        assert!(!gate.evaluate(&[true, false, false, true], &mut || false));
+    }
+
+    #[test]
+    fn test_name_clashes() {
+        majority();
+        assert!(register(&Impostor("test_majority")).is_err());
+        assert!(register(&Impostor("nand")).is_err());
+        half_adder();
+        assert!(register(&Impostor("test_carry")).is_err());
+    }
+
+    #[test]
+    fn test_outputs() {
+        let (sum, carry) = half_adder();
+        assert_eq!(half_adder(), (sum, carry));
+        assert_eq!(Gate::from_name("test_sum"), Some(sum));
+        assert_eq!(carry.name(), "test_carry");
+        assert!(sum.evaluate(&[true, false], &mut || false));
+        assert!(!carry.evaluate(&[true, false], &mut || false));
+        assert!(carry.evaluate(&[true, true], &mut || false));
+    }
+
+    #[test]
+    fn test_order() {
+        let majority = majority();
+        let (sum, carry) = half_adder();
+        let ids: Vec<Gate> = all().into_iter().map(Gate::Custom).collect();
+        let position = |gate: Gate| ids.iter().position(|&id| id == gate).unwrap();
+        // Listed first despite being registered later, with its outputs together
+        assert!(position(sum) < position(majority));
+        assert_eq!(position(carry), position(sum) + 1);
+        let Gate::Custom(sum_id) = sum else {
+            unreachable!()
+        };
+        assert_eq!(Gate::Custom(sum_id.next()), carry);
+    }
+}
@@ -0,0 +1,361 @@
+//! Routes wires around nodes and, where it can, around other wires.
+//!
+//! Wires run horizontally, vertically or at 45 degrees, so a route is found with A*
+//! over the grid, moving from each cell to any of the 8 around it.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    coords::Coords,
+    graph::{
+        elbow::Elbow,
+        node::NodeId,
+        synth::choose_elbow,
+        wire::{Wire, WireId},
+        Graph,
+    },
+};
+
+/// How far beyond the box around its ends a route may wander.
+const MARGIN: i32 = 8;
+/// Most states looked at before giving up, so that a long or blocked route can't stall the editor.
+const MAX_EXPANSIONS: usize = 20_000;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+/// Added at each corner, so that routes are as simple as they can be.
+const TURN_COST: u32 = 5;
+/// Added for each cell shared with another wire, which is allowed so that wires can cross.
+const WIRE_COST: u32 = 30;
+
+/// Clockwise from east, as steps to the next cell.
+const DIRECTIONS: [(i32, i32); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// Direction of the step into a cell, or `None` for the start.
+type State = (Coords, Option<usize>);
+
+/// Shape for a wire, to apply with [`Wire::set_route`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Route {
+    pub elbow: Elbow,
+    pub via: Vec<Coords>,
+}
+
+impl Wire {
+    pub fn set_route(&mut self, route: Route) {
+        self.elbow = route.elbow;
+        self.via = route.via;
+    }
+}
+
+/// Every cell from `start` to `end` inclusive, which must be in a straight line.
+fn leg_cells(start: &Coords, end: &Coords) -> impl Iterator<Item = Coords> {
+    let (dx, dy) = ((end.x - start.x).signum(), (end.y - start.y).signum());
+    let steps = (end.x - start.x).abs().max((end.y - start.y).abs());
+    let start = *start;
+    (0..=steps).map(move |i| Coords {
+        x: start.x + i * dx,
+        y: start.y + i * dy,
+    })
+}
+
+/// Least cost from `a` to `b` if nothing were in the way.
+fn octile_distance(a: &Coords, b: &Coords) -> u32 {
+    let (dx, dy) = ((b.x - a.x).unsigned_abs(), (b.y - a.y).unsigned_abs());
+    STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+}
+
+/// Reduces the cells of a route to its ends and the cells where it changes direction.
+fn corners(cells: &[Coords]) -> Vec<Coords> {
+    let direction = |a: &Coords, b: &Coords| (b.x - a.x, b.y - a.y);
+    let mut corners = vec![cells[0]];
+    for window in cells.windows(3) {
+        if direction(&window[0], &window[1]) != direction(&window[1], &window[2]) {
+            corners.push(window[1]);
+        }
+    }
+    if cells.len() > 1 {
+        corners.push(cells[cells.len() - 1]);
+    }
+    corners
+}
+
+/// Simplest way to draw a wire through `corners`: a single elbow where one fits.
+fn route_through(corners: &[Coords]) -> Route {
+    let (start, end) = (&corners[0], &corners[corners.len() - 1]);
+    let fallback = choose_elbow(start, end);
+    match &corners[1..corners.len() - 1] {
+        [] => Route {
+            elbow: fallback,
+            via: Vec::new(),
+        },
+        [corner] => match Elbow::ALL
+            .into_iter()
+            .find(|elbow| elbow.joint(start, end) == *corner)
+        {
+            Some(elbow) => Route {
+                elbow,
+                via: Vec::new(),
+            },
+            None => Route {
+                elbow: fallback,
+                via: vec![*corner],
+            },
+        },
+        // Each leg between corners is straight, so the elbow makes no difference
+        via => Route {
+            elbow: fallback,
+            via: via.to_vec(),
+        },
+    }
+}
+
+impl Graph {
+    /// Cells covered by every wire but `ignore`.
+    fn wire_cells(&self, ignore: Option<WireId>) -> HashSet<Coords> {
+        self.wires()
+            .filter(|&(id, _)| Some(id) != ignore)
+            .flat_map(|(_, wire)| {
+                let start = self.node(wire.input).coords;
+                let end = self.node(wire.output).coords;
+                let corners = wire.corners(&start, &end);
+                corners
+                    .windows(2)
+                    .flat_map(|leg| leg_cells(&leg[0], &leg[1]))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Cheapest route from `start` to `end` that keeps off every node between them,
+    /// and off every wire but `ignore` unless it has to cross one.
+    ///
+    /// `None` if there's no way through close to the ends, or it takes too long to find one.
+    pub fn route(&self, start: &Coords, end: &Coords, ignore: Option<WireId>) -> Option<Route> {
+        if start == end {
+            return Some(route_through(&[*start, *end]));
+        }
+        let nodes: HashSet<Coords> = self.nodes().map(|(_, node)| node.coords).collect();
+        let wires = self.wire_cells(ignore);
+        let (min_x, max_x) = (start.x.min(end.x) - MARGIN, start.x.max(end.x) + MARGIN);
+        let (min_y, max_y) = (start.y.min(end.y) - MARGIN, start.y.max(end.y) + MARGIN);
+
+        let mut best: HashMap<State, u32> = HashMap::from([((*start, None), 0)]);
+        let mut came_from: HashMap<State, State> = HashMap::new();
+        // Least estimated total cost first, then most cost so far, which is closest to the end
+        let mut open = BinaryHeap::from([(
+            Reverse(octile_distance(start, end)),
+            0,
+            start.x,
+            start.y,
+            None,
+        )]);
+        let mut expanded = 0;
+
+        while let Some((_, cost, x, y, direction)) = open.pop() {
+            let coords = Coords { x, y };
+            let state = (coords, direction);
+            if coords == *end {
+                let mut cells = vec![coords];
+                let mut state = state;
+                while let Some(&prev) = came_from.get(&state) {
+                    cells.push(prev.0);
+                    state = prev;
+                }
+                cells.reverse();
+                return Some(route_through(&corners(&cells)));
+            }
+            if best.get(&state).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            expanded += 1;
+            if expanded > MAX_EXPANSIONS {
+                return None;
+            }
+
+            for (i, (dx, dy)) in DIRECTIONS.into_iter().enumerate() {
+                let next = Coords {
+                    x: coords.x + dx,
+                    y: coords.y + dy,
+                };
+                if !(min_x..=max_x).contains(&next.x)
+                    || !(min_y..=max_y).contains(&next.y)
+                    || (next != *end && nodes.contains(&next))
+                {
+                    continue;
+                }
+                let mut next_cost = cost
+                    + match dx != 0 && dy != 0 {
+                        true => DIAGONAL_COST,
+                        false => STRAIGHT_COST,
+                    };
+                if direction.is_some_and(|direction| direction != i) {
+                    next_cost += TURN_COST;
+                }
+                if next != *end && wires.contains(&next) {
+                    next_cost += WIRE_COST;
+                }
+
+                let next_state = (next, Some(i));
+                if best.get(&next_state).is_some_and(|&best| next_cost >= best) {
+                    continue;
+                }
+                best.insert(next_state, next_cost);
+                came_from.insert(next_state, state);
+                let estimate = next_cost + octile_distance(&next, end);
+                open.push((Reverse(estimate), next_cost, next.x, next.y, Some(i)));
+            }
+        }
+        None
+    }
+
+    /// Adds a wire routed around whatever's in the way,
+    /// or with the usual elbow if there's no way round.
+    pub fn add_routed_wire(&mut self, src: NodeId, dest: NodeId) -> WireId {
+        let (start, end) = (self.node(src).coords, self.node(dest).coords);
+        let id = self.add_wire(src, dest, &choose_elbow(&start, &end));
+        if let Some(route) = self.route(&start, &end, Some(id)) {
+            self.wire_mut(id).set_route(route);
+        }
+        id
+    }
+
+    /// Routes each of `ids` again in turn, so later wires avoid earlier ones.
+    /// Wires with no way round are left as they were. Returns how many were routed.
+    pub fn reroute_wires(&mut self, ids: &[WireId]) -> usize {
+        let mut routed = 0;
+        for &id in ids {
+            let wire = self.wire(id);
+            let (start, end) = (self.node(wire.input).coords, self.node(wire.output).coords);
+            if let Some(route) = self.route(&start, &end, Some(id)) {
+                self.wire_mut(id).set_route(route);
+                routed += 1;
+            }
+        }
+        routed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::gate::{Gate, Gate1};
+
+    const BUFFER: Gate = Gate::G1(Gate1::Buffer);
+
+    fn wire_along(route: Route) -> Wire {
+        let mut wire = Wire::new(NodeId(0), NodeId(1), &Elbow::HorzS);
+        wire.set_route(route);
+        wire
+    }
+
+    #[test]
+    fn test_corners() {
+        let cells: Vec<Coords> = [(0, 0), (1, 0), (2, 1), (3, 2), (3, 3)]
+            .into_iter()
+            .map(|(x, y)| Coords { x, y })
+            .collect();
+        assert_eq!(
+            corners(&cells),
+            vec![cells[0], cells[1], cells[3], cells[4]]
+        );
+        assert_eq!(corners(&cells[..1]), vec![cells[0]]);
+    }
+
+    #[test]
+    fn test_unobstructed() {
+        let graph = Graph::new();
+        let start = Coords { x: 0, y: 0 };
+
+        let straight = graph.route(&start, &Coords { x: 5, y: 0 }, None).unwrap();
+        assert!(straight.via.is_empty());
+
+        // One diagonal and one straight leg fit a single elbow
+        let end = Coords { x: 6, y: 2 };
+        let bent = graph.route(&start, &end, None).unwrap();
+        assert!(bent.via.is_empty());
+        assert!([Elbow::DiagS, Elbow::DiagE].contains(&bent.elbow));
+
+        assert!(graph.route(&start, &start, None).is_some());
+    }
+
+    #[test]
+    fn test_around_node() {
+        let mut graph = Graph::new();
+        let start = Coords { x: 0, y: 0 };
+        let end = Coords { x: 6, y: 0 };
+        let blocker = Coords { x: 3, y: 0 };
+        for coords in [start, end, blocker] {
+            graph.add_node(&BUFFER, &coords);
+        }
+
+        let wire = wire_along(graph.route(&start, &end, None).unwrap());
+        assert!(!wire.is_intersecting_coords(&start, &end, &blocker));
+        // Going round takes more than one elbow
+        assert!(!wire.via.is_empty());
+    }
+
+    #[test]
+    fn test_walled_in() {
+        let mut graph = Graph::new();
+        let start = Coords { x: 0, y: 0 };
+        graph.add_node(&BUFFER, &start);
+        for (dx, dy) in DIRECTIONS {
+            graph.add_node(&BUFFER, &Coords { x: dx, y: dy });
+        }
+        assert_eq!(graph.route(&start, &Coords { x: 5, y: 5 }, None), None);
+        // Gives up on a far end rather than searching all the way round it
+        assert_eq!(graph.route(&Coords { x: 400, y: 0 }, &start, None), None);
+    }
+
+    #[test]
+    fn test_long_route() {
+        // Ties go to the state closest to the end, so an open straight run is found quickly
+        let start = Coords { x: 0, y: 0 };
+        let end = Coords { x: 5000, y: 0 };
+        let route = Graph::new().route(&start, &end, None).unwrap();
+        assert!(route.via.is_empty());
+    }
+
+    #[test]
+    fn test_reroute_wires() {
+        let mut graph = Graph::new();
+        let start = Coords { x: 0, y: 0 };
+        let end = Coords { x: 6, y: 0 };
+        let blocker = Coords { x: 3, y: 0 };
+        let a = graph.add_node(&BUFFER, &start);
+        let b = graph.add_node(&BUFFER, &end);
+        graph.add_node(&BUFFER, &blocker);
+        let wire = graph.add_wire(a, b, &Elbow::HorzS);
+        assert!(graph
+            .wire(wire)
+            .is_intersecting_coords(&start, &end, &blocker));
+
+        assert_eq!(graph.reroute_wires(&[wire]), 1);
+        assert!(!graph
+            .wire(wire)
+            .is_intersecting_coords(&start, &end, &blocker));
+
+        // Added wires are routed from the start
+        let (start, end) = (Coords { x: 0, y: 6 }, Coords { x: 6, y: 6 });
+        let blocker = Coords { x: 3, y: 6 };
+        let c = graph.add_node(&BUFFER, &start);
+        let d = graph.add_node(&BUFFER, &end);
+        graph.add_node(&BUFFER, &blocker);
+        let routed = graph.add_routed_wire(c, d);
+        assert!(!graph
+            .wire(routed)
+            .is_intersecting_coords(&start, &end, &blocker));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use crate::{
+    formats::vcd::Recorder,
+    graph::{eval::XorShift, node::NodeId, Graph},
+};
+
+/// Runs the graph one tick at a time, every node taking the value its gate gives
+/// for what its inputs output on the previous tick.
+///
+/// Unlike [`Evaluator`](crate::graph::eval::Evaluator), loops are allowed,
+/// and oscillate or hold their value as real ones would.
+pub struct Simulation {
+    tick: u64,
+    /// Output of every node, indexed by [`NodeId`].
+    state: Vec<bool>,
+    rng: XorShift,
+    /// Samples every tick while recording.
+    pub recorder: Option<Recorder>,
+    /// Nodes plotted in the waveform panel.
+    pub probes: Vec<NodeId>,
+    /// Nodes held at a value instead of evaluating their gate, such as inputs under test.
+    driven: HashMap<NodeId, bool>,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation {
+    pub fn new() -> Self {
+        Self {
+            tick: 0,
+            state: Vec::new(),
+            rng: XorShift::new(0x5EED),
+            recorder: None,
+            probes: Vec::new(),
+            driven: HashMap::new(),
+        }
+    }
+
+    /// Starts again from tick 0 with every node off, discarding any recording and probes.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Output of every node, indexed by [`NodeId`].
+    pub fn state(&self) -> &[bool] {
+        &self.state
+    }
+
+    /// Holds `id` at `value` from the next tick on.
+    pub fn drive(&mut self, id: NodeId, value: bool) {
+        self.driven.insert(id, value);
+    }
+
+    /// Starts or stops plotting `id` in the waveform panel, returning whether it's now probed.
+    pub fn toggle_probe(&mut self, id: NodeId) -> bool {
+        match self.probes.iter().position(|&probe| probe == id) {
+            Some(i) => {
+                self.probes.remove(i);
+                false
+            }
+            None => {
+                self.probes.push(id);
+                true
+            }
+        }
+    }
+
+    pub fn output(&self, id: NodeId) -> bool {
+        self.state.get(id.0).copied().unwrap_or(false)
+    }
+
+    pub fn step(&mut self, graph: &Graph) {
+        let inputs = graph.input_lists();
+        // Nodes added since the last tick start off
+        self.state.resize(inputs.len(), false);
+
+        let mut values: Vec<bool> = Vec::new();
+        let rng = &mut self.rng;
+        let next: Vec<bool> = graph
+            .nodes()
+            .map(|(id, node)| {
+                if let Some(&value) = self.driven.get(&id) {
+                    return value;
+                }
+                values.clear();
+                values.extend(inputs[id.0].iter().map(|input| self.state[input.0]));
+                node.gate.evaluate(&values, &mut || rng.next_bool())
+            })
+            .collect();
+        self.state = next;
+        self.tick += 1;
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.sample(self.tick, &self.state);
+        }
+    }
+}
+
+impl Graph {
+    /// Ticks for a change at any node to reach every output, one per gate along the longest path.
+    /// `None` if the graph has loops, which might never settle.
+    pub fn settle_ticks(&self) -> Option<u64> {
+        if !self.find_combinational_loops().is_empty() {
+            return None;
+        }
+        let inputs = self.input_lists();
+        let mut depth = vec![0u64; self.nodes.len()];
+        // Components come out of Tarjan's algorithm sinks-first
+        for id in self
+            .strongly_connected_components()
+            .into_iter()
+            .rev()
+            .flatten()
+        {
+            depth[id.0] = inputs[id.0]
+                .iter()
+                .map(|input| depth[input.0] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        Some(depth.into_iter().max().unwrap_or(0) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coords::Coords,
+        graph::{
+            elbow::Elbow,
+            gate::{Gate, Gate0, Gate1, GateN},
+        },
+    };
+
+    #[test]
+    fn test_gate_delay() {
+        let mut graph = Graph::new();
+        let one = graph.add_node(&Gate::G0(Gate0::Always), &Coords { x: 0, y: 0 });
+        let buffer = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 1, y: 0 });
+        graph.add_wire(one, buffer, &Elbow::HorzS);
+
+        let mut simulation = Simulation::new();
+        simulation.step(&graph);
+        assert!(simulation.output(one));
+        assert!(!simulation.output(buffer));
+        simulation.step(&graph);
+        assert!(simulation.output(buffer));
+        assert_eq!(simulation.tick(), 2);
+    }
+
+    #[test]
+    fn test_drive() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: 0, y: 0 });
+        let nor = graph.add_node(&Gate::GN(GateN::Nor), &Coords { x: 1, y: 0 });
+        graph.add_wire(a, nor, &Elbow::HorzS);
+        assert_eq!(graph.settle_ticks(), Some(2));
+
+        let mut simulation = Simulation::new();
+        simulation.drive(a, true);
+        simulation.step(&graph);
+        assert!(simulation.output(a));
+        assert!(simulation.output(nor));
+        simulation.step(&graph);
+        assert!(!simulation.output(nor));
+    }
+
+    #[test]
+    fn test_oscillator() {
+        let mut graph = Graph::new();
+        let nor = graph.add_node(&Gate::GN(GateN::Nor), &Coords::default());
+        graph.add_wire(nor, nor, &Elbow::HorzS);
+        assert_eq!(graph.settle_ticks(), None);
+
+        let mut simulation = Simulation::new();
+        let outputs: Vec<bool> = (0..4)
+            .map(|_| {
+                simulation.step(&graph);
+                simulation.output(nor)
+            })
+            .collect();
+        assert_eq!(outputs, vec![true, false, true, false]);
+    }
+}
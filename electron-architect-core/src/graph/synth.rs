@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use crate::{
+    coords::Coords,
+    graph::{
+        elbow::Elbow,
+        expr::Expr,
+        gate::{Gate, Gate0, Gate1, GateN},
+        node::NodeId,
+        Graph,
+    },
+};
+
+/// Grid cells between each level of logic.
+pub const COLUMN_SPACING: i32 = 3;
+/// Grid cells between nodes on the same level.
+pub const ROW_SPACING: i32 = 2;
+/// How far down to look for free space before giving up.
+const MAX_PLACEMENT_ATTEMPTS: i32 = 64;
+
+enum Cell {
+    /// Named input, which is only placed if the graph doesn't already have it.
+    Input(String),
+    Gate(Gate, Vec<usize>),
+}
+
+/// The expression flattened into gates ahead of placement.
+/// Arguments always come before the gates that use them.
+struct Plan {
+    cells: Vec<Cell>,
+    levels: Vec<i32>,
+    inputs: HashMap<String, usize>,
+}
+
+impl Plan {
+    fn push(&mut self, cell: Cell, level: i32) -> usize {
+        self.cells.push(cell);
+        self.levels.push(level);
+        self.cells.len() - 1
+    }
+
+    fn add(&mut self, expr: &Expr) -> usize {
+        match expr {
+            Expr::Var(name) => match self.inputs.get(name) {
+                Some(&i) => i,
+                None => {
+                    let i = self.push(Cell::Input(name.clone()), 0);
+                    self.inputs.insert(name.clone(), i);
+                    i
+                }
+            },
+            Expr::Const(value) => {
+                let gate = if *value { Gate0::Always } else { Gate0::Never };
+                self.push(Cell::Gate(Gate::G0(gate), Vec::new()), 0)
+            }
+            // There's no inverter, but a single-input nor is one
+            Expr::Not(arg) => self.add_gate(GateN::Nor, std::slice::from_ref(arg)),
+            Expr::Gate(gate, args) => self.add_gate(*gate, args),
+        }
+    }
+
+    fn add_gate(&mut self, gate: GateN, args: &[Expr]) -> usize {
+        let args: Vec<usize> = args.iter().map(|arg| self.add(arg)).collect();
+        let level = 1 + args.iter().map(|&i| self.levels[i]).max().unwrap_or(0);
+        self.push(Cell::Gate(Gate::GN(gate), args), level)
+    }
+}
+
+/// Leaves horizontally so that left-to-right layouts read cleanly, then angles into the destination.
+pub fn choose_elbow(start: &Coords, end: &Coords) -> Elbow {
+    if (end.x - start.x).abs() >= (end.y - start.y).abs() {
+        Elbow::DiagE
+    } else {
+        Elbow::DiagS
+    }
+}
+
+impl Graph {
+    /// Builds `expr` out of gates, laid out left to right from `origin`,
+    /// feeding a buffer named `output`.
+    ///
+    /// Variables that already name a node are wired from that node,
+    /// the rest become new named buffers.
+    pub fn synthesise(
+        &mut self,
+        output: &str,
+        expr: &Expr,
+        origin: &Coords,
+    ) -> Result<NodeId, String> {
+        if self.find_node_by_name(output).is_some() {
+            return Err(format!("A node is already named `{output}`"));
+        }
+
+        let mut plan = Plan {
+            cells: Vec::new(),
+            levels: Vec::new(),
+            inputs: HashMap::new(),
+        };
+        let root = plan.add(expr);
+        // The output would be wired into itself, or a second node would take its name
+        if plan.inputs.contains_key(output) {
+            return Err(format!("`{output}` can't be synthesised from itself"));
+        }
+        let output_level = plan.levels[root] + 1;
+        let output_cell = plan.push(
+            Cell::Gate(Gate::G1(Gate1::Buffer), vec![root]),
+            output_level,
+        );
+
+        let existing: Vec<Option<NodeId>> = plan
+            .cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Input(name) => self.find_node_by_name(name),
+                Cell::Gate(..) => None,
+            })
+            .collect();
+
+        // Offsets from the origin, stacking each level's cells in order
+        let mut rows_used: HashMap<i32, i32> = HashMap::new();
+        let offsets: Vec<Coords> = plan
+            .levels
+            .iter()
+            .zip(&existing)
+            .map(|(&level, existing)| {
+                if existing.is_some() {
+                    return Coords::default(); // Never placed
+                }
+                let row = rows_used.entry(level).or_insert(0);
+                *row += 1;
+                Coords {
+                    x: level * COLUMN_SPACING,
+                    y: (*row - 1) * ROW_SPACING,
+                }
+            })
+            .collect();
+
+        let placed_at = |shift: i32| {
+            offsets
+                .iter()
+                .zip(&existing)
+                .filter_map(move |(offset, existing)| {
+                    existing.is_none().then_some(Coords {
+                        x: origin.x + offset.x,
+                        y: origin.y + offset.y + shift,
+                    })
+                })
+        };
+        let shift = (0..MAX_PLACEMENT_ATTEMPTS)
+            .map(|attempt| attempt * ROW_SPACING)
+            .find(|&shift| {
+                placed_at(shift).all(|coords| self.find_node_at_coords(&coords).is_none())
+            })
+            .ok_or("Not enough free space near the cursor")?;
+
+        let mut ids: Vec<NodeId> = Vec::with_capacity(plan.cells.len());
+        for (i, cell) in plan.cells.iter().enumerate() {
+            if let Some(id) = existing[i] {
+                ids.push(id);
+                continue;
+            }
+
+            let coords = Coords {
+                x: origin.x + offsets[i].x,
+                y: origin.y + offsets[i].y + shift,
+            };
+            let id = match cell {
+                Cell::Input(name) => {
+                    let id = self.add_node(&Gate::G1(Gate1::Buffer), &coords);
+                    self.node_mut(id).name = Some(name.clone());
+                    id
+                }
+                Cell::Gate(gate, args) => {
+                    let id = self.add_node(gate, &coords);
+                    for &arg in args {
+                        let start = self.node(ids[arg]).coords;
+                        self.add_wire(ids[arg], id, &choose_elbow(&start, &coords));
+                    }
+                    id
+                }
+            };
+            ids.push(id);
+        }
+
+        let output_id = ids[output_cell];
+        self.node_mut(output_id).name = Some(output.to_string());
+        Ok(output_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesise_matches_expression() {
+        let mut graph = Graph::new();
+        let expr: Expr = "(a & b) ^ !c".parse().unwrap();
+        let output = graph.synthesise("y", &expr, &Coords::default()).unwrap();
+
+        assert_eq!(
+            graph.expression(output).unwrap().to_string(),
+            "N-Gate xor(N-Gate and(a, b), N-Gate nor(c))"
+        );
+
+        let a = graph.find_node_by_name("a").unwrap();
+        let b = graph.find_node_by_name("b").unwrap();
+        let c = graph.find_node_by_name("c").unwrap();
+        let table = graph.truth_table(&[a, b, c], &[output]).unwrap();
+        assert_eq!(table.minterms(0), vec![0, 2, 4, 7]);
+    }
+
+    #[test]
+    fn test_synthesise_reuses_inputs() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x: -5, y: 0 });
+        graph.node_mut(a).name = Some("a".to_string());
+
+        let expr: Expr = "a | !a".parse().unwrap();
+        graph.synthesise("y", &expr, &Coords::default()).unwrap();
+
+        // Just the or, the nor and the output buffer
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(
+            graph.source_nodes(graph.find_node_by_name("y").unwrap()),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn test_synthesise_avoids_existing_nodes() {
+        let mut graph = Graph::new();
+        let blocker = graph.add_node(&Gate::G1(Gate1::Buffer), &Coords::default());
+
+        let expr: Expr = "a".parse().unwrap();
+        graph.synthesise("y", &expr, &Coords::default()).unwrap();
+
+        let a = graph.find_node_by_name("a").unwrap();
+        assert_ne!(a, blocker);
+        assert!(graph.node(a).coords != Coords::default());
+    }
+
+    #[test]
+    fn test_duplicate_output() {
+        let mut graph = Graph::new();
+        let expr: Expr = "a".parse().unwrap();
+        graph.synthesise("y", &expr, &Coords::default()).unwrap();
+        assert!(graph.synthesise("y", &expr, &Coords::default()).is_err());
+    }
+
+    #[test]
+    fn test_output_used_as_input() {
+        let mut graph = Graph::new();
+        let expr: Expr = "y & a".parse().unwrap();
+        let error = graph
+            .synthesise("y", &expr, &Coords::default())
+            .unwrap_err();
+        assert!(error.contains("from itself"));
+        assert_eq!(graph.nodes.len(), 0);
+    }
+}
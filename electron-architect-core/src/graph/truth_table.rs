@@ -0,0 +1,340 @@
+use std::{collections::HashMap, fmt};
+
+use crate::graph::{node::NodeId, Graph};
+
+/// Exhaustive simulation doubles in cost with every input, so anything wider is refused.
+pub const MAX_TRUTH_TABLE_INPUTS: usize = 16;
+
+pub struct TruthTableRow {
+    pub inputs: Vec<bool>,
+    pub outputs: Vec<bool>,
+}
+
+pub struct TruthTable {
+    pub input_names: Vec<String>,
+    pub output_names: Vec<String>,
+    /// Counts up in binary, with the first input as the most significant bit.
+    pub rows: Vec<TruthTableRow>,
+}
+
+impl TruthTable {
+    fn column_names(&self) -> impl Iterator<Item = &String> {
+        self.input_names.iter().chain(self.output_names.iter())
+    }
+
+    /// Column names, lined up with [`TruthTable::format_row`].
+    pub fn header(&self) -> String {
+        let inputs = self.input_names.join(" ");
+        let outputs = self.output_names.join(" ");
+        format!("{inputs} | {outputs}")
+    }
+
+    pub fn format_row(&self, row: &TruthTableRow) -> String {
+        let cells: Vec<String> = self
+            .column_names()
+            .zip(row.inputs.iter().chain(row.outputs.iter()))
+            .map(|(name, &value)| format!("{:>width$}", value as u8, width = name.len()))
+            .collect();
+        let (inputs, outputs) = cells.split_at(row.inputs.len());
+        format!("{} | {}", inputs.join(" "), outputs.join(" "))
+    }
+
+    /// Names are quoted where they need to be, as unnamed nodes' coordinates have commas.
+    pub fn to_csv(&self) -> String {
+        let names: Vec<String> = self.column_names().map(|name| csv_field(name)).collect();
+        let mut csv = names.join(",");
+        csv.push('\n');
+        for row in &self.rows {
+            let cells: Vec<&str> = row
+                .inputs
+                .iter()
+                .chain(row.outputs.iter())
+                .map(|&value| if value { "1" } else { "0" })
+                .collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Reads back the output of [`TruthTable::to_csv`].
+    ///
+    /// The file doesn't record which columns are inputs, so they are taken to be
+    /// the leading columns that count up in binary down the rows.
+    pub fn from_csv(csv: &str) -> Result<Self, String> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let names = split_csv_line(lines.next().ok_or("Truth table is empty")?)?;
+
+        let cells: Vec<Vec<bool>> = lines
+            .map(|line| {
+                let row = split_csv_line(line)?
+                    .iter()
+                    .map(|cell| match cell.as_str() {
+                        "0" => Ok(false),
+                        "1" => Ok(true),
+                        other => Err(format!("Truth table cell `{other}` is not 0 or 1")),
+                    })
+                    .collect::<Result<Vec<bool>, String>>()?;
+                match row.len() == names.len() {
+                    true => Ok(row),
+                    false => Err(format!("Truth table row `{line}` has the wrong width")),
+                }
+            })
+            .collect::<Result<_, String>>()?;
+
+        let num_inputs = cells.len().trailing_zeros() as usize;
+        if !cells.len().is_power_of_two() || num_inputs > names.len() {
+            return Err(format!(
+                "{} rows can't be a complete truth table",
+                cells.len()
+            ));
+        }
+        for (combination, row) in cells.iter().enumerate() {
+            let counts_up =
+                (0..num_inputs).all(|i| row[i] == ((combination >> (num_inputs - 1 - i)) & 1 == 1));
+            if !counts_up {
+                return Err(format!("Truth table row {combination} is out of order"));
+            }
+        }
+
+        let mut input_names = names;
+        let output_names = input_names.split_off(num_inputs);
+        Ok(Self {
+            input_names,
+            output_names,
+            rows: cells
+                .into_iter()
+                .map(|mut inputs| {
+                    let outputs = inputs.split_off(num_inputs);
+                    TruthTableRow { inputs, outputs }
+                })
+                .collect(),
+        })
+    }
+
+    /// Rows where the named output is high, as their index.
+    pub fn minterms(&self, output: usize) -> Vec<u32> {
+        (0u32..)
+            .zip(&self.rows)
+            .filter(|(_, row)| row.outputs[output])
+            .map(|(minterm, _)| minterm)
+            .collect()
+    }
+}
+
+/// Quoted as RFC 4180 has it if it has a comma, quote or line break in it.
+fn csv_field(s: &str) -> String {
+    match s.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => s.to_string(),
+    }
+}
+
+/// Fields of one line of CSV, trimmed unless they're quoted.
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|ch| *ch == ' ' || *ch == '\t').is_some() {}
+        let field = match chars.next_if_eq(&'"') {
+            Some(_) => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        // A doubled quote is one quote in the field
+                        Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                        Some('"') => break,
+                        Some(ch) => field.push(ch),
+                        None => return Err(format!("Unclosed quote in `{line}`")),
+                    }
+                }
+                while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+                field
+            }
+            None => {
+                let field: String = std::iter::from_fn(|| chars.next_if(|ch| *ch != ',')).collect();
+                field.trim().to_string()
+            }
+        };
+        fields.push(field);
+        match chars.next() {
+            Some(',') => {}
+            None => return Ok(fields),
+            Some(ch) => {
+                return Err(format!(
+                    "Unexpected `{ch}` after a quoted field in `{line}`"
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.header())?;
+        for row in &self.rows {
+            writeln!(f, "{}", self.format_row(row))?;
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// The node's name if it has one, otherwise its coordinates.
+    pub fn node_label(&self, id: NodeId) -> String {
+        let node = self.node(id);
+        match &node.name {
+            Some(name) => name.clone(),
+            None => node.coords.to_string(),
+        }
+    }
+
+    /// Simulates every combination of `inputs`, holding each input node at the given value
+    /// regardless of its gate, and records the resulting state of `outputs`.
+    pub fn truth_table(&self, inputs: &[NodeId], outputs: &[NodeId]) -> Result<TruthTable, String> {
+        if inputs.len() > MAX_TRUTH_TABLE_INPUTS {
+            return Err(format!(
+                "Truth table has {} inputs, the limit is {MAX_TRUTH_TABLE_INPUTS}",
+                inputs.len()
+            ));
+        }
+
+        let mut evaluator = self.evaluator()?;
+        let mut overrides: HashMap<NodeId, bool> = HashMap::new();
+        let mut rows = Vec::with_capacity(1 << inputs.len());
+
+        for combination in 0..(1usize << inputs.len()) {
+            let input_values: Vec<bool> = (0..inputs.len())
+                .map(|i| (combination >> (inputs.len() - 1 - i)) & 1 == 1)
+                .collect();
+            overrides.clear();
+            overrides.extend(inputs.iter().copied().zip(input_values.iter().copied()));
+
+            let state = evaluator.evaluate(&overrides);
+            rows.push(TruthTableRow {
+                inputs: input_values,
+                outputs: outputs.iter().map(|id| state[id.0]).collect(),
+            });
+        }
+
+        Ok(TruthTable {
+            input_names: inputs.iter().map(|&id| self.node_label(id)).collect(),
+            output_names: outputs.iter().map(|&id| self.node_label(id)).collect(),
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coords::Coords,
+        graph::{
+            elbow::Elbow,
+            gate::{Gate, Gate1, GateN},
+        },
+    };
+
+    fn named_node(graph: &mut Graph, gate: Gate, x: i32, name: &str) -> NodeId {
+        let id = graph.add_node(&gate, &Coords { x, y: 0 });
+        graph.node_mut(id).name = Some(name.to_string());
+        id
+    }
+
+    /// `y = a xor b`, `c = a and b`
+    fn half_adder() -> (Graph, Vec<NodeId>, Vec<NodeId>) {
+        let mut graph = Graph::new();
+        let a = named_node(&mut graph, Gate::G1(Gate1::Buffer), 0, "a");
+        let b = named_node(&mut graph, Gate::G1(Gate1::Buffer), 1, "b");
+        let y = named_node(&mut graph, Gate::GN(GateN::Xor), 2, "y");
+        let c = named_node(&mut graph, Gate::GN(GateN::And), 3, "c");
+        for input in [a, b] {
+            graph.add_wire(input, y, &Elbow::HorzS);
+            graph.add_wire(input, c, &Elbow::HorzS);
+        }
+        (graph, vec![a, b], vec![y, c])
+    }
+
+    #[test]
+    fn test_half_adder() {
+        let (graph, inputs, outputs) = half_adder();
+        let table = graph.truth_table(&inputs, &outputs).unwrap();
+
+        let outputs: Vec<Vec<bool>> = table.rows.iter().map(|row| row.outputs.clone()).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                vec![false, false],
+                vec![true, false],
+                vec![true, false],
+                vec![false, true],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let (graph, inputs, outputs) = half_adder();
+        let table = graph.truth_table(&inputs, &outputs).unwrap();
+        assert_eq!(
+            table.to_csv(),
+            "a,b,y,c\n0,0,0,0\n0,1,1,0\n1,0,1,0\n1,1,0,1\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let (graph, inputs, outputs) = half_adder();
+        let csv = graph.truth_table(&inputs, &outputs).unwrap().to_csv();
+        let table = TruthTable::from_csv(&csv).unwrap();
+        assert_eq!(table.input_names, vec!["a", "b"]);
+        assert_eq!(table.output_names, vec!["y", "c"]);
+        assert_eq!(table.to_csv(), csv);
+        assert_eq!(table.minterms(0), vec![1, 2]);
+        assert_eq!(table.minterms(1), vec![3]);
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        let (mut graph, inputs, outputs) = half_adder();
+        graph.node_mut(inputs[0]).name = None;
+        graph.node_mut(outputs[0]).name = Some("say \"hi\"".to_string());
+        let csv = graph.truth_table(&inputs, &outputs).unwrap().to_csv();
+        assert!(
+            csv.starts_with("\"(0, 0)\",b,\"say \"\"hi\"\"\",c\n"),
+            "{csv}"
+        );
+
+        let table = TruthTable::from_csv(&csv).unwrap();
+        assert_eq!(table.input_names, vec!["(0, 0)", "b"]);
+        assert_eq!(table.output_names, vec!["say \"hi\"", "c"]);
+        assert_eq!(table.to_csv(), csv);
+
+        assert!(TruthTable::from_csv("\"a,y\n0,0\n1,1\n").is_err());
+        assert!(TruthTable::from_csv("\"a\"b,y\n0,0\n1,1\n").is_err());
+    }
+
+    #[test]
+    fn test_csv_out_of_order() {
+        assert!(TruthTable::from_csv("a,y\n1,0\n0,1\n").is_err());
+        assert!(TruthTable::from_csv("a,y\n0,0\n1,2\n").is_err());
+        assert!(TruthTable::from_csv("a,b,y\n0,0,0\n0,1,1\n1,0,1\n").is_err());
+    }
+
+    #[test]
+    fn test_too_many_inputs() {
+        let mut graph = Graph::new();
+        let inputs: Vec<NodeId> = (0..=MAX_TRUTH_TABLE_INPUTS as i32)
+            .map(|x| graph.add_node(&Gate::G1(Gate1::Buffer), &Coords { x, y: 0 }))
+            .collect();
+        assert!(graph.truth_table(&inputs, &[]).is_err());
+    }
+
+    #[test]
+    fn test_loop_is_rejected() {
+        let (mut graph, inputs, outputs) = half_adder();
+        graph.add_wire(outputs[0], inputs[0], &Elbow::HorzS);
+        assert!(graph.truth_table(&inputs, &outputs).is_err());
+    }
+}
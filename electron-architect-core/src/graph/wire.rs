@@ -0,0 +1,88 @@
+#[cfg(feature = "raylib")]
+use raylib::prelude::*;
+
+use crate::{
+    coords::Coords,
+    graph::{elbow::Elbow, node::NodeId},
+};
+
+/// Index of a [`Wire`] within the [`Graph`](crate::graph::Graph) that owns it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct WireId(pub usize);
+
+pub struct Wire {
+    pub input: NodeId,
+    pub output: NodeId,
+    /// Shape of each leg of the wire, between its ends and any points it's routed via.
+    pub elbow: Elbow,
+    /// Points the wire passes through on its way, in order. Usually empty.
+    pub via: Vec<Coords>,
+}
+
+impl Wire {
+    pub fn new(input: NodeId, output: NodeId, elbow: &Elbow) -> Self {
+        Self {
+            input,
+            output,
+            elbow: *elbow,
+            via: Vec::new(),
+        }
+    }
+
+    /// Every point the wire changes direction at, along with its ends,
+    /// so that it's a straight line from each to the next.
+    ///
+    /// `start` and `end` are the coordinates of the input and output nodes respectively.
+    pub fn corners(&self, start: &Coords, end: &Coords) -> Vec<Coords> {
+        let mut corners = vec![*start];
+        for point in self.via.iter().chain([end]) {
+            let prev = *corners.last().unwrap();
+            let joint = self.elbow.joint(&prev, point);
+            if joint != prev && joint != *point {
+                corners.push(joint);
+            }
+            corners.push(*point);
+        }
+        corners
+    }
+
+    /// `start` and `end` are the coordinates of the input and output nodes respectively.
+    pub fn is_intersecting_coords(&self, start: &Coords, end: &Coords, search: &Coords) -> bool {
+        self.corners(start, end)
+            .windows(2)
+            .any(|leg| search.is_intersecting_coords(&leg[0], &leg[1]))
+    }
+
+    /// `start` and `end` are the coordinates of the input and output nodes respectively.
+    #[cfg(feature = "raylib")]
+    pub fn draw(&self, d: &mut impl RaylibDraw, start: &Coords, end: &Coords, color: Color) {
+        for leg in self.corners(start, end).windows(2) {
+            d.draw_line_v(leg[0].into_position(), leg[1].into_position(), color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corners() {
+        let start = Coords { x: 0, y: 0 };
+        let end = Coords { x: 4, y: 2 };
+        let mut wire = Wire::new(NodeId(0), NodeId(1), &Elbow::HorzS);
+        assert_eq!(
+            wire.corners(&start, &end),
+            vec![start, Coords { x: 4, y: 0 }, end]
+        );
+
+        // Straight legs have no joint of their own
+        wire.via = vec![Coords { x: 0, y: 3 }, Coords { x: 4, y: 3 }];
+        assert_eq!(
+            wire.corners(&start, &end),
+            vec![start, Coords { x: 0, y: 3 }, Coords { x: 4, y: 3 }, end]
+        );
+        assert!(wire.is_intersecting_coords(&start, &end, &Coords { x: 2, y: 3 }));
+        assert!(!wire.is_intersecting_coords(&start, &end, &Coords { x: 2, y: 0 }));
+    }
+}
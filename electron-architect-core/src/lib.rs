@@ -0,0 +1,11 @@
+//! The circuit model, simulation and file formats behind Electron Architect,
+//! usable without a window.
+//!
+//! Drawing with raylib is behind the `raylib` feature.
+
+pub mod common_traits;
+pub mod coords;
+pub mod formats;
+pub mod graph;
+pub mod plugins;
+pub mod sat;
@@ -0,0 +1,60 @@
+//! Custom gates built into Electron Architect. Add a [`GatePlugin`] here and list it in [`PLUGINS`]
+//! to make it available in the editor's gate scroll cycle, save files, scripts and `ea-sim`.
+
+use crate::{
+    coords::Coords,
+    graph::plugin::{self, Canvas, GatePlugin},
+};
+
+/// True when most of its inputs are. With three inputs, the carry out of a full adder.
+struct Majority;
+
+impl GatePlugin for Majority {
+    fn name(&self) -> &'static str {
+        "majority"
+    }
+
+    fn glyph(&self) -> &'static str {
+        "M"
+    }
+
+    fn max_inputs(&self) -> usize {
+        usize::MAX
+    }
+
+    fn evaluate(&self, inputs: &[bool], _: usize) -> bool {
+        let high = inputs.iter().filter(|&&input| input).count();
+        high * 2 > inputs.len()
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas) {
+        let radius = Coords::GRID_RADIUS;
+        canvas.circle((0.0, 0.0), radius);
+        // Underlined, just clear of the circle
+        canvas.line((-radius, radius + 2.0), (radius, radius + 2.0));
+    }
+}
+
+const PLUGINS: &[&dyn GatePlugin] = &[&Majority];
+
+/// Call before loading anything that might use the plugins. Safe to call more than once.
+pub fn register_all() -> Result<(), String> {
+    for &gate in PLUGINS {
+        plugin::register(gate)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::gate::Gate;
+
+    #[test]
+    fn test_majority() {
+        register_all().unwrap();
+        let gate = Gate::from_name("majority").unwrap();
+        assert!(gate.evaluate(&[true, true, false], &mut || false));
+        assert!(!gate.evaluate(&[true, false, false, true], &mut || false));
+    }
+}
@@ -0,0 +1,488 @@
+//! A small CDCL SAT solver: two watched literals per clause, learning a clause from each conflict
+//! at its first unique implication point, backjumping, VSIDS branching with saved phases
+//! and restarts.
+
+/// A variable, or its negation when negative. Variables are numbered from 1.
+pub type Lit = i32;
+
+/// A boolean formula in conjunctive normal form.
+pub struct Cnf {
+    num_vars: usize,
+    clauses: Vec<Vec<Lit>>,
+}
+
+/// What [`Cnf::solve`] found.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Solution {
+    /// A satisfying assignment, indexed by variable number minus one.
+    Satisfiable(Vec<bool>),
+    Unsatisfiable,
+    /// Ran out of conflicts before finding either.
+    Unknown,
+}
+
+impl Default for Cnf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cnf {
+    pub fn new() -> Self {
+        Self {
+            num_vars: 0,
+            clauses: Vec::new(),
+        }
+    }
+
+    pub fn new_var(&mut self) -> Lit {
+        self.num_vars += 1;
+        self.num_vars as Lit
+    }
+
+    /// At least one of the literals must be true.
+    pub fn add_clause(&mut self, clause: Vec<Lit>) {
+        self.clauses.push(clause);
+    }
+
+    /// Gives up with [`Solution::Unknown`] after `max_conflicts` conflicts,
+    /// so that a hard formula can't run on indefinitely.
+    pub fn solve(&self, max_conflicts: u64) -> Solution {
+        let mut solver = Solver::new(self.num_vars);
+        for clause in &self.clauses {
+            let clause: Vec<usize> = clause.iter().map(|&lit| encode(lit)).collect();
+            if !solver.add_clause(clause) {
+                return Solution::Unsatisfiable;
+            }
+        }
+        solver.solve(max_conflicts)
+    }
+}
+
+/// Literals are numbered `2 * var` when positive and `2 * var + 1` when negated,
+/// with variables numbered from 0.
+fn encode(lit: Lit) -> usize {
+    2 * (lit.unsigned_abs() as usize - 1) + (lit < 0) as usize
+}
+
+fn var(lit: usize) -> usize {
+    lit / 2
+}
+
+fn is_negated(lit: usize) -> bool {
+    lit & 1 == 1
+}
+
+fn negate(lit: usize) -> usize {
+    lit ^ 1
+}
+
+/// Conflicts before the first restart, which grows by [`RESTART_GROWTH`] each time.
+const FIRST_RESTART: u64 = 100;
+const RESTART_GROWTH: f64 = 1.5;
+/// How much of their activity variables keep after each conflict.
+const ACTIVITY_DECAY: f64 = 0.95;
+/// Activities are scaled down past this, before they overflow.
+const MAX_ACTIVITY: f64 = 1e100;
+
+struct Solver {
+    clauses: Vec<Vec<usize>>,
+    /// Clauses whose first or second literal is the index, to look at once it becomes false.
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    /// Decision level each variable was assigned at.
+    levels: Vec<usize>,
+    /// Clause that forced each variable, with the variable's literal first, or `None` for decisions.
+    reasons: Vec<Option<usize>>,
+    /// Assigned literals in the order they were assigned.
+    trail: Vec<usize>,
+    /// Length of the trail before each decision.
+    decisions: Vec<usize>,
+    /// Trail index of the next assignment to propagate.
+    propagated: usize,
+    activity: Vec<f64>,
+    activity_increment: f64,
+    /// Value each variable last had, which it's given again when next decided.
+    phases: Vec<bool>,
+    /// Scratch space for conflict analysis.
+    seen: Vec<bool>,
+}
+
+impl Solver {
+    fn new(num_vars: usize) -> Self {
+        Self {
+            clauses: Vec::new(),
+            watches: vec![Vec::new(); 2 * num_vars],
+            values: vec![None; num_vars],
+            levels: vec![0; num_vars],
+            reasons: vec![None; num_vars],
+            trail: Vec::new(),
+            decisions: Vec::new(),
+            propagated: 0,
+            activity: vec![0.0; num_vars],
+            activity_increment: 1.0,
+            phases: vec![false; num_vars],
+            seen: vec![false; num_vars],
+        }
+    }
+
+    fn value(&self, lit: usize) -> Option<bool> {
+        self.values[var(lit)].map(|value| value != is_negated(lit))
+    }
+
+    fn level(&self) -> usize {
+        self.decisions.len()
+    }
+
+    fn assign(&mut self, lit: usize, reason: Option<usize>) {
+        let var = var(lit);
+        self.values[var] = Some(!is_negated(lit));
+        self.levels[var] = self.level();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Adds one of the formula's clauses, before solving starts.
+    /// Returns false if the formula can already be seen to be unsatisfiable.
+    fn add_clause(&mut self, mut clause: Vec<usize>) -> bool {
+        clause.sort_unstable();
+        clause.dedup();
+        if clause.windows(2).any(|pair| pair[1] == negate(pair[0])) {
+            return true; // Always satisfied
+        }
+        match clause[..] {
+            [] => false,
+            [lit] => match self.value(lit) {
+                Some(value) => value,
+                None => {
+                    self.assign(lit, None);
+                    true
+                }
+            },
+            _ => {
+                self.watch(clause);
+                true
+            }
+        }
+    }
+
+    fn watch(&mut self, clause: Vec<usize>) -> usize {
+        let id = self.clauses.len();
+        self.watches[clause[0]].push(id);
+        self.watches[clause[1]].push(id);
+        self.clauses.push(clause);
+        id
+    }
+
+    /// Assigns the last free literal of every clause that is otherwise false,
+    /// returning a clause that has become entirely false, if any.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let false_lit = negate(self.trail[self.propagated]);
+            self.propagated += 1;
+
+            let watching = std::mem::take(&mut self.watches[false_lit]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for (i, &id) in watching.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watching[i..]);
+                    break;
+                }
+                let clause = &mut self.clauses[id];
+                if clause[0] == false_lit {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[var(first)].is_some_and(|value| value != is_negated(first)) {
+                    kept.push(id);
+                    continue;
+                }
+
+                // Watch another literal that isn't false, if there is one
+                let values = &self.values;
+                let replacement = clause[2..]
+                    .iter()
+                    .position(|&lit| values[var(lit)] != Some(is_negated(lit)));
+                if let Some(k) = replacement {
+                    clause.swap(1, k + 2);
+                    let lit = clause[1];
+                    self.watches[lit].push(id);
+                    continue;
+                }
+
+                kept.push(id);
+                match self.value(first) {
+                    Some(false) => conflict = Some(id),
+                    _ => self.assign(first, Some(id)),
+                }
+            }
+            self.watches[false_lit] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.activity_increment;
+        if self.activity[var] > MAX_ACTIVITY {
+            for activity in &mut self.activity {
+                *activity /= MAX_ACTIVITY;
+            }
+            self.activity_increment /= MAX_ACTIVITY;
+        }
+    }
+
+    /// Learns a clause from a conflict that's false under the current assignment but,
+    /// after backjumping, forces a different value for the last variable decided.
+    /// Returns the clause, with that literal first, and the level to backjump to.
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let mut learnt = vec![0]; // Replaced with the asserting literal
+        let mut at_this_level = 0;
+        let mut implied: Option<usize> = None;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        loop {
+            // A reason clause's first literal is the one it implied
+            let skip = implied.is_some() as usize;
+            for i in skip..self.clauses[clause].len() {
+                let lit = self.clauses[clause][i];
+                let var = var(lit);
+                if self.seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                self.seen[var] = true;
+                self.bump(var);
+                match self.levels[var] == self.level() {
+                    true => at_this_level += 1,
+                    false => learnt.push(lit),
+                }
+            }
+            // Walk back to the latest assignment involved in the conflict
+            loop {
+                index -= 1;
+                if self.seen[var(self.trail[index])] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            self.seen[var(lit)] = false;
+            implied = Some(lit);
+            at_this_level -= 1;
+            if at_this_level == 0 {
+                break;
+            }
+            clause = self.reasons[var(lit)].expect("Only decisions have no reason");
+        }
+        learnt[0] = negate(implied.unwrap());
+        for &lit in &learnt[1..] {
+            self.seen[var(lit)] = false;
+        }
+        self.activity_increment /= ACTIVITY_DECAY;
+
+        // Watch the literal from the latest level after the asserting one
+        let mut level = 0;
+        if let Some(i) = (1..learnt.len()).max_by_key(|&i| self.levels[var(learnt[i])]) {
+            learnt.swap(1, i);
+            level = self.levels[var(learnt[1])];
+        }
+        (learnt, level)
+    }
+
+    /// Undoes every assignment made after `level`'s decision.
+    fn backjump(&mut self, level: usize) {
+        if level >= self.level() {
+            return;
+        }
+        let len = self.decisions[level];
+        for lit in self.trail.drain(len..) {
+            let var = var(lit);
+            self.phases[var] = self.values[var].unwrap();
+            self.values[var] = None;
+            self.reasons[var] = None;
+        }
+        self.decisions.truncate(level);
+        self.propagated = len;
+    }
+
+    /// The most active unassigned variable, or `None` once they're all assigned.
+    fn pick_branch(&self) -> Option<usize> {
+        (0..self.values.len())
+            .filter(|&var| self.values[var].is_none())
+            .max_by(|&a, &b| self.activity[a].total_cmp(&self.activity[b]))
+    }
+
+    fn solve(&mut self, max_conflicts: u64) -> Solution {
+        let mut conflicts = 0;
+        let mut next_restart = FIRST_RESTART;
+        let mut restart_interval = FIRST_RESTART as f64;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.level() == 0 {
+                    return Solution::Unsatisfiable;
+                }
+                conflicts += 1;
+                if conflicts > max_conflicts {
+                    return Solution::Unknown;
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backjump(level);
+                match learnt.len() {
+                    1 => self.assign(learnt[0], None),
+                    _ => {
+                        let lit = learnt[0];
+                        let id = self.watch(learnt);
+                        self.assign(lit, Some(id));
+                    }
+                }
+                continue;
+            }
+
+            if conflicts >= next_restart {
+                restart_interval *= RESTART_GROWTH;
+                next_restart = conflicts + restart_interval as u64;
+                self.backjump(0);
+            }
+            match self.pick_branch() {
+                None => {
+                    let model = self.values.iter().map(|value| value.unwrap()).collect();
+                    return Solution::Satisfiable(model);
+                }
+                Some(var) => {
+                    self.decisions.push(self.trail.len());
+                    let lit = 2 * var + !self.phases[var] as usize;
+                    self.assign(lit, None);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_CONFLICTS: u64 = 100_000;
+
+    #[test]
+    fn test_satisfiable() {
+        let mut cnf = Cnf::new();
+        let a = cnf.new_var();
+        let b = cnf.new_var();
+        let c = cnf.new_var();
+        cnf.add_clause(vec![a, b]);
+        cnf.add_clause(vec![-a, c]);
+        cnf.add_clause(vec![-b, -c]);
+        cnf.add_clause(vec![a]);
+
+        assert_eq!(
+            cnf.solve(MAX_CONFLICTS),
+            Solution::Satisfiable(vec![true, false, true])
+        );
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut cnf = Cnf::new();
+        let a = cnf.new_var();
+        let b = cnf.new_var();
+        cnf.add_clause(vec![a, b]);
+        cnf.add_clause(vec![a, -b]);
+        cnf.add_clause(vec![-a, b]);
+        cnf.add_clause(vec![-a, -b]);
+        assert_eq!(cnf.solve(MAX_CONFLICTS), Solution::Unsatisfiable);
+    }
+
+    /// Pigeons that each need a hole of their own, with one hole too few.
+    fn pigeonhole(holes: usize) -> Cnf {
+        let mut cnf = Cnf::new();
+        let pigeons: Vec<Vec<Lit>> = (0..=holes)
+            .map(|_| (0..holes).map(|_| cnf.new_var()).collect())
+            .collect();
+        for pigeon in &pigeons {
+            cnf.add_clause(pigeon.clone());
+        }
+        for hole in 0..holes {
+            for a in 0..pigeons.len() {
+                for b in a + 1..pigeons.len() {
+                    cnf.add_clause(vec![-pigeons[a][hole], -pigeons[b][hole]]);
+                }
+            }
+        }
+        cnf
+    }
+
+    #[test]
+    fn test_learning() {
+        // Needs conflicts deep in the search, and backjumps past several levels
+        assert_eq!(pigeonhole(5).solve(MAX_CONFLICTS), Solution::Unsatisfiable);
+    }
+
+    #[test]
+    fn test_gives_up() {
+        // Far beyond what a few conflicts can show
+        assert_eq!(pigeonhole(10).solve(10), Solution::Unknown);
+    }
+
+    #[test]
+    fn test_random_formulas() {
+        // Checked against trying every assignment, with a fixed linear congruential generator
+        let mut state: u64 = 1;
+        let mut random = |below: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % below
+        };
+        for _ in 0..300 {
+            let mut cnf = Cnf::new();
+            let num_vars = 3 + random(8) as usize;
+            for _ in 0..num_vars {
+                cnf.new_var();
+            }
+            for _ in 0..num_vars * 4 {
+                let clause = (0..1 + random(3))
+                    .map(|_| {
+                        let var = 1 + random(num_vars as u64) as Lit;
+                        match random(2) {
+                            0 => var,
+                            _ => -var,
+                        }
+                    })
+                    .collect();
+                cnf.add_clause(clause);
+            }
+
+            let satisfies = |model: &[bool]| {
+                cnf.clauses.iter().all(|clause| {
+                    clause
+                        .iter()
+                        .any(|&lit| model[lit.unsigned_abs() as usize - 1] == (lit > 0))
+                })
+            };
+            let any = (0..1u32 << num_vars).any(|row| {
+                let model: Vec<bool> = (0..num_vars).map(|i| row & (1 << i) != 0).collect();
+                satisfies(&model)
+            });
+            match cnf.solve(MAX_CONFLICTS) {
+                Solution::Satisfiable(model) => assert!(satisfies(&model)),
+                Solution::Unsatisfiable => assert!(!any),
+                Solution::Unknown => panic!("Small formulas shouldn't need many conflicts"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_trivial_clauses() {
+        let mut cnf = Cnf::new();
+        let a = cnf.new_var();
+        cnf.add_clause(vec![a, -a]);
+        cnf.add_clause(vec![-a, -a]);
+        assert_eq!(cnf.solve(MAX_CONFLICTS), Solution::Satisfiable(vec![false]));
+
+        cnf.add_clause(Vec::new());
+        assert_eq!(cnf.solve(MAX_CONFLICTS), Solution::Unsatisfiable);
+    }
+}
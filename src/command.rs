@@ -0,0 +1,720 @@
+use std::{
+    fs,
+    str::{FromStr, SplitWhitespace},
+};
+
+use electron_architect_core::{
+    coords::Coords,
+    formats::{
+        self, save,
+        vcd::{Recorder, MAX_RECORDED_CHANGES},
+        vectors, ExportOptions,
+    },
+    graph::{
+        equiv::{check_equivalence, Equivalence, MAX_EXHAUSTIVE_INPUTS, MAX_SAT_CONFLICTS},
+        expr::Expr,
+        minimise::minimise,
+        node::NodeId,
+        sim::Simulation,
+        truth_table::TruthTable,
+        wire::WireId,
+        Graph,
+    },
+};
+
+use crate::{console::Console, cursor::Cursor, script};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SynthSource {
+    Expr(Expr),
+    /// Path to a truth table CSV
+    TruthTable(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Recording {
+    /// Names of the nodes to record, or empty for all of them
+    Start(Vec<String>),
+    /// Path to write the VCD file to
+    Stop(String),
+}
+
+/// Something typed into the console prompt.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// `name <label>`
+    ///
+    /// Names the node under the cursor so other commands can refer to it.
+    Name(String),
+
+    /// `truth <inputs...> -> <outputs...> [> file.csv]`
+    ///
+    /// Prints the truth table of the named nodes and saves it as CSV.
+    Truth {
+        inputs: Vec<String>,
+        outputs: Vec<String>,
+        path: String,
+    },
+
+    /// `expr <output>`
+    ///
+    /// Prints the boolean expression for the named node, then its minimised form.
+    Expression(String),
+
+    /// `synth <output> = <expression>` or `synth <output> < file.csv`
+    ///
+    /// Lays out gates computing the expression, or the minimised form of
+    /// the output's column in the truth table, next to the cursor.
+    Synth { output: String, source: SynthSource },
+
+    /// `save <path>`
+    Save(String),
+
+    /// `load <path>`
+    ///
+    /// Replaces the current graph.
+    Load(String),
+
+    /// `equiv <path> [path]`
+    ///
+    /// Checks that two circuits compute the same outputs from the same inputs,
+    /// pairing them up by name. With one path, compares against the current graph.
+    Equiv(Option<String>, String),
+
+    /// `export <path> [clustered] [scale <n>] [region <x> <y> <x> <y>]`
+    ///
+    /// Writes the graph in the format given by the file extension:
+    /// `.v` for Verilog, `.vhd` for VHDL, `.blif`, `.dot` for Graphviz,
+    /// which can group each separate circuit into a cluster,
+    /// or `.svg` and `.png` images, which can be scaled up and cropped to a region.
+    Export {
+        path: String,
+        options: ExportOptions,
+    },
+
+    /// `import <path>`
+    ///
+    /// Replaces the current graph with one read from another tool's format:
+    /// `.blif`, `.circ` from Logisim, or a save file from the original Electron Architect.
+    Import(String),
+
+    /// `record start [nodes...]` or `record stop [file.vcd]`
+    ///
+    /// Records the outputs of the named nodes, or every node, on each tick of the simulation,
+    /// then writes them out as a VCD waveform.
+    Record(Recording),
+
+    /// `probe <nodes...>`
+    ///
+    /// Adds the named nodes to the waveform panel, or removes them if they're already there.
+    Probe(Vec<String>),
+
+    /// `test <script>`
+    ///
+    /// Runs a test vector script against the circuit it names, or the current graph,
+    /// and reports the first tick an output doesn't match.
+    Test(String),
+
+    /// `script <path>`
+    ///
+    /// Runs a Rhai script, which can build onto the graph and drive the simulation.
+    Script(String),
+
+    /// `route [all]`
+    ///
+    /// Routes the wires between selected nodes, or every wire, around nodes and each other.
+    Route { all: bool },
+}
+
+fn single_arg(args: &str, usage: &str) -> Result<String, String> {
+    match args.split_whitespace().count() {
+        1 => Ok(args.to_string()),
+        _ => Err(format!("Expected `{usage}`")),
+    }
+}
+
+fn parse_truth(args: &str) -> Result<Command, String> {
+    let (inputs, rest) = args
+        .split_once("->")
+        .ok_or("Expected `truth <inputs...> -> <outputs...>`")?;
+    let (outputs, path) = match rest.split_once('>') {
+        Some((outputs, path)) => (outputs, path.trim()),
+        None => (rest, Command::DEFAULT_TRUTH_TABLE_PATH),
+    };
+
+    let inputs: Vec<String> = inputs.split_whitespace().map(String::from).collect();
+    let outputs: Vec<String> = outputs.split_whitespace().map(String::from).collect();
+    if outputs.is_empty() {
+        return Err("Truth table needs at least one output".to_string());
+    }
+    if path.is_empty() {
+        return Err("Expected a file name after `>`".to_string());
+    }
+
+    Ok(Command::Truth {
+        inputs,
+        outputs,
+        path: path.to_string(),
+    })
+}
+
+fn parse_synth(args: &str) -> Result<Command, String> {
+    let split = args
+        .find(['=', '<'])
+        .ok_or("Expected `synth <output> = <expression>`")?;
+    let output = args[..split].trim();
+    let rest = args[split + 1..].trim();
+    if output.split_whitespace().count() != 1 {
+        return Err("Expected a single output name before the `=`".to_string());
+    }
+
+    let source = match &args[split..split + 1] {
+        "=" => SynthSource::Expr(rest.parse()?),
+        _ if rest.is_empty() => return Err("Expected a file name after `<`".to_string()),
+        _ => SynthSource::TruthTable(rest.to_string()),
+    };
+    Ok(Command::Synth {
+        output: output.to_string(),
+        source,
+    })
+}
+
+fn next_number(fields: &mut SplitWhitespace, what: &str) -> Result<i32, String> {
+    fields
+        .next()
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| format!("Expected {what}"))
+}
+
+fn parse_export(args: &str) -> Result<Command, String> {
+    let mut fields = args.split_whitespace();
+    let path = fields.next().ok_or("Expected `export <path>`")?;
+    let mut options = ExportOptions::default();
+    while let Some(option) = fields.next() {
+        match option {
+            "clustered" => options.clustered = true,
+            "scale" => {
+                let scale: f32 = fields
+                    .next()
+                    .and_then(|field| field.parse().ok())
+                    .ok_or("Expected a number after `scale`")?;
+                if !(scale.is_finite() && scale > 0.0) {
+                    return Err("Scale must be more than 0".to_string());
+                }
+                options.scale = Some(scale);
+            }
+            "region" => {
+                let mut coords = [0; 4];
+                for value in &mut coords {
+                    *value = next_number(&mut fields, "four coordinates after `region`")?;
+                }
+                let [x1, y1, x2, y2] = coords;
+                options.region = Some((Coords { x: x1, y: y1 }, Coords { x: x2, y: y2 }));
+            }
+            other => return Err(format!("Unknown export option `{other}`")),
+        }
+    }
+    Ok(Command::Export {
+        path: path.to_string(),
+        options,
+    })
+}
+
+fn parse_record(args: &str) -> Result<Command, String> {
+    let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let rest = rest.trim();
+    match action {
+        "start" => Ok(Command::Record(Recording::Start(
+            rest.split_whitespace().map(String::from).collect(),
+        ))),
+        "stop" if rest.is_empty() => Ok(Command::Record(Recording::Stop(
+            Command::DEFAULT_RECORDING_PATH.to_string(),
+        ))),
+        "stop" => single_arg(rest, "record stop [file.vcd]")
+            .map(|path| Command::Record(Recording::Stop(path))),
+        _ => Err("Expected `record start [nodes...]` or `record stop [file.vcd]`".to_string()),
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (keyword, args) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let args = args.trim();
+        match keyword {
+            "name" => single_arg(args, "name <label>").map(Command::Name),
+            "truth" => parse_truth(args),
+            "expr" => single_arg(args, "expr <output>").map(Command::Expression),
+            "synth" => parse_synth(args),
+            "save" => single_arg(args, "save <path>").map(Command::Save),
+            "load" => single_arg(args, "load <path>").map(Command::Load),
+            "equiv" => match args.split_whitespace().collect::<Vec<&str>>()[..] {
+                [right] => Ok(Command::Equiv(None, right.to_string())),
+                [left, right] => Ok(Command::Equiv(Some(left.to_string()), right.to_string())),
+                _ => Err("Expected `equiv <path> [path]`".to_string()),
+            },
+            "export" => parse_export(args),
+            "import" => single_arg(args, "import <path>").map(Command::Import),
+            "record" => parse_record(args),
+            "test" => single_arg(args, "test <script>").map(Command::Test),
+            "script" => single_arg(args, "script <path>").map(Command::Script),
+            "route" => match args {
+                "" => Ok(Command::Route { all: false }),
+                "all" => Ok(Command::Route { all: true }),
+                _ => Err("Expected `route [all]`".to_string()),
+            },
+            "probe" if args.is_empty() => Err("Expected `probe <nodes...>`".to_string()),
+            "probe" => Ok(Command::Probe(
+                args.split_whitespace().map(String::from).collect(),
+            )),
+            _ => Err(format!("Unknown command `{keyword}`")),
+        }
+    }
+}
+
+fn find_named_node(graph: &Graph, name: &str) -> Result<NodeId, String> {
+    graph
+        .find_node_by_name(name)
+        .ok_or_else(|| format!("No node named `{name}`"))
+}
+
+fn find_named_nodes(graph: &Graph, names: &[String]) -> Result<Vec<NodeId>, String> {
+    names
+        .iter()
+        .map(|name| find_named_node(graph, name))
+        .collect()
+}
+
+impl Command {
+    const DEFAULT_TRUTH_TABLE_PATH: &'static str = "truth_table.csv";
+    const DEFAULT_RECORDING_PATH: &'static str = "recording.vcd";
+
+    /// Tables longer than this are only written to file in full.
+    const MAX_CONSOLE_ROWS: usize = 32;
+
+    /// Node outputs mean nothing once the graph is replaced.
+    fn restart_simulation(simulation: &mut Simulation, console: &mut Console) {
+        if simulation.recorder.is_some() {
+            console.warn("Stopped recording, as the graph was replaced");
+        }
+        simulation.reset();
+    }
+
+    /// `selection` is the nodes picked out with the select tool.
+    pub fn execute(
+        self,
+        graph: &mut Graph,
+        simulation: &mut Simulation,
+        console: &mut Console,
+        cursor: &Cursor,
+        selection: &[NodeId],
+    ) {
+        if let Err(e) = self.try_execute(graph, simulation, console, cursor, selection) {
+            console.err(e);
+        }
+    }
+
+    fn try_execute(
+        self,
+        graph: &mut Graph,
+        simulation: &mut Simulation,
+        console: &mut Console,
+        cursor: &Cursor,
+        selection: &[NodeId],
+    ) -> Result<(), String> {
+        match self {
+            Command::Name(name) => {
+                let id = graph
+                    .find_node_at_coords(&cursor.coords)
+                    .ok_or("Hover over a node to name it")?;
+                if graph
+                    .find_node_by_name(&name)
+                    .is_some_and(|other| other != id)
+                {
+                    return Err(format!("Another node is already named `{name}`"));
+                }
+                graph.node_mut(id).name = Some(name.clone());
+                console.log(format!("Named node at {} `{name}`", cursor.coords));
+            }
+
+            Command::Truth {
+                inputs,
+                outputs,
+                path,
+            } => {
+                let inputs = find_named_nodes(graph, &inputs)?;
+                let outputs = find_named_nodes(graph, &outputs)?;
+                let table = graph.truth_table(&inputs, &outputs)?;
+
+                console.log(table.header());
+                for row in table.rows.iter().take(Self::MAX_CONSOLE_ROWS) {
+                    console.log(table.format_row(row));
+                }
+                if table.rows.len() > Self::MAX_CONSOLE_ROWS {
+                    console.log(format!(
+                        "...{} more rows",
+                        table.rows.len() - Self::MAX_CONSOLE_ROWS
+                    ));
+                }
+
+                fs::write(&path, table.to_csv())
+                    .map_err(|e| format!("Couldn't write `{path}`: {e}"))?;
+                console.log(format!("Saved truth table to `{path}`"));
+            }
+
+            Command::Expression(name) => {
+                let id = find_named_node(graph, &name)?;
+                let expr = graph.expression(id)?;
+                let minimised = graph.minimised_expression(id)?;
+                console.log(format!("{name} = {expr}"));
+                console.log(format!("{name} = {minimised} (minimised)"));
+
+                let (gates, minimised_gates) = (graph.gate_count(id), minimised.gate_count());
+                if minimised_gates < gates {
+                    console.log(format!(
+                        "Design uses {gates} gates where {minimised_gates} would do"
+                    ));
+                }
+            }
+
+            Command::Synth { output, source } => {
+                let expr = match source {
+                    SynthSource::Expr(expr) => expr,
+                    SynthSource::TruthTable(path) => {
+                        let csv = fs::read_to_string(&path)
+                            .map_err(|e| format!("Couldn't read `{path}`: {e}"))?;
+                        let table = TruthTable::from_csv(&csv)?;
+                        let column = table
+                            .output_names
+                            .iter()
+                            .position(|name| *name == output)
+                            .ok_or_else(|| format!("`{path}` has no output named `{output}`"))?;
+                        minimise(&table.input_names, &table.minterms(column))
+                    }
+                };
+                graph.synthesise(&output, &expr, &cursor.coords)?;
+                console.log(format!("Synthesised {output} = {expr}"));
+            }
+
+            Command::Save(path) => {
+                save::save_file(graph, &path)?;
+                console.log(format!("Saved to `{path}`"));
+            }
+
+            Command::Load(path) => {
+                *graph = save::load_file(&path)?;
+                Self::restart_simulation(simulation, console);
+                console.log(format!("Loaded `{path}`"));
+            }
+
+            Command::Equiv(left_path, right_path) => {
+                let right = save::load_file(&right_path)?;
+                let left = match &left_path {
+                    Some(path) => Some(save::load_file(path)?),
+                    None => None,
+                };
+                let left = left.as_ref().unwrap_or(graph);
+
+                let num_inputs = left.input_ports().len();
+                let method = match num_inputs <= MAX_EXHAUSTIVE_INPUTS {
+                    true => format!("all {} input combinations", 1usize << num_inputs),
+                    false => "SAT".to_string(),
+                };
+                match check_equivalence(left, &right)? {
+                    Equivalence::Equivalent => {
+                        console.log(format!("Circuits are equivalent (checked {method})"));
+                    }
+                    Equivalence::Unknown => {
+                        console.warn(format!(
+                            "Couldn't tell whether the circuits are equivalent within {MAX_SAT_CONFLICTS} SAT conflicts"
+                        ));
+                    }
+                    Equivalence::Different(counterexample) => {
+                        console.warn(format!("Circuits differ: {counterexample}"));
+                    }
+                }
+            }
+
+            Command::Export { path, options } => {
+                formats::export_file(graph, &path, &options)?;
+                console.log(format!("Exported to `{path}`"));
+            }
+
+            Command::Import(path) => {
+                let (imported, warnings) = formats::import_file(&path)?;
+                *graph = imported;
+                Self::restart_simulation(simulation, console);
+                for warning in warnings {
+                    console.warn(warning);
+                }
+                console.log(format!("Imported `{path}`"));
+            }
+
+            Command::Record(Recording::Start(names)) => {
+                if simulation.recorder.is_some() {
+                    return Err("Already recording".to_string());
+                }
+                let signals = match names.is_empty() {
+                    true => graph.nodes().map(|(id, _)| id).collect(),
+                    false => find_named_nodes(graph, &names)?,
+                };
+                console.log(format!(
+                    "Recording {} nodes from tick {}",
+                    signals.len(),
+                    simulation.tick()
+                ));
+                simulation.recorder = Some(Recorder::new(signals, MAX_RECORDED_CHANGES));
+            }
+
+            Command::Test(path) => {
+                let report = vectors::run_file(&path, graph)?;
+                match report.passed() {
+                    true => console.log(report.to_string()),
+                    false => console.err(report.to_string()),
+                }
+            }
+
+            Command::Script(path) => {
+                script::run_file(&path, graph, simulation, console)?;
+                console.log(format!("Ran `{path}`"));
+            }
+
+            Command::Route { all } => {
+                let ids: Vec<WireId> = graph
+                    .wires()
+                    .filter(|(_, wire)| {
+                        all || (selection.contains(&wire.input) && selection.contains(&wire.output))
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+                if ids.is_empty() {
+                    return Err(match all {
+                        true => "There are no wires to route".to_string(),
+                        false => "Select some wired nodes with the select tool first".to_string(),
+                    });
+                }
+                let routed = graph.reroute_wires(&ids);
+                console.log(format!("Routed {routed} of {} wire(s)", ids.len()));
+                if routed < ids.len() {
+                    console.warn("Wires with no way round were left as they were");
+                }
+            }
+
+            Command::Probe(names) => {
+                for (id, name) in find_named_nodes(graph, &names)?.into_iter().zip(&names) {
+                    match simulation.toggle_probe(id) {
+                        true => console.log(format!("Probing `{name}`")),
+                        false => console.log(format!("Stopped probing `{name}`")),
+                    }
+                }
+            }
+
+            Command::Record(Recording::Stop(path)) => {
+                let recorder = simulation.recorder.take().ok_or("Not recording")?;
+                if recorder.len() == MAX_RECORDED_CHANGES {
+                    console.warn("Recording was full, so only the latest changes were kept");
+                }
+                fs::write(&path, recorder.to_vcd(graph, "graph"))
+                    .map_err(|e| format!("Couldn't write `{path}`: {e}"))?;
+                match recorder.span() {
+                    Some((first, last)) => console.log(format!(
+                        "Saved ticks {first} to {last} of {} nodes to `{path}`",
+                        recorder.signals().len()
+                    )),
+                    None => console.log(format!("Saved an empty recording to `{path}`")),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_name() {
+        assert_eq!("name  a ".parse(), Ok(Command::Name("a".to_string())));
+        assert!("name".parse::<Command>().is_err());
+        assert!("name a b".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_truth() {
+        assert_eq!(
+            "truth a b -> y".parse(),
+            Ok(Command::Truth {
+                inputs: names("a b"),
+                outputs: names("y"),
+                path: Command::DEFAULT_TRUTH_TABLE_PATH.to_string(),
+            })
+        );
+        assert_eq!(
+            "truth a->y c > adder.csv".parse(),
+            Ok(Command::Truth {
+                inputs: names("a"),
+                outputs: names("y c"),
+                path: "adder.csv".to_string(),
+            })
+        );
+        assert!("truth a b".parse::<Command>().is_err());
+        assert!("truth a ->".parse::<Command>().is_err());
+        assert!("truth a -> y >".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!("expr y".parse(), Ok(Command::Expression("y".to_string())));
+        assert!("expr".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_synth() {
+        assert_eq!(
+            "synth y = a & !b".parse(),
+            Ok(Command::Synth {
+                output: "y".to_string(),
+                source: SynthSource::Expr("and(a, !b)".parse().unwrap()),
+            })
+        );
+        assert_eq!(
+            "synth y < adder.csv".parse(),
+            Ok(Command::Synth {
+                output: "y".to_string(),
+                source: SynthSource::TruthTable("adder.csv".to_string()),
+            })
+        );
+        assert!("synth y".parse::<Command>().is_err());
+        assert!("synth = a".parse::<Command>().is_err());
+        assert!("synth y = a &".parse::<Command>().is_err());
+        assert!("synth y <".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_files() {
+        assert_eq!("save a.ea".parse(), Ok(Command::Save("a.ea".to_string())));
+        assert_eq!("load a.ea".parse(), Ok(Command::Load("a.ea".to_string())));
+        assert_eq!(
+            "equiv b.ea".parse(),
+            Ok(Command::Equiv(None, "b.ea".to_string()))
+        );
+        assert_eq!(
+            "equiv a.ea b.ea".parse(),
+            Ok(Command::Equiv(Some("a.ea".to_string()), "b.ea".to_string()))
+        );
+        assert_eq!(
+            "export adder.v".parse(),
+            Ok(Command::Export {
+                path: "adder.v".to_string(),
+                options: ExportOptions::default(),
+            })
+        );
+        assert_eq!(
+            "export adder.dot clustered".parse(),
+            Ok(Command::Export {
+                path: "adder.dot".to_string(),
+                options: ExportOptions {
+                    clustered: true,
+                    ..Default::default()
+                },
+            })
+        );
+        assert_eq!(
+            "export adder.png region 0 -1 8 4 scale 4".parse(),
+            Ok(Command::Export {
+                path: "adder.png".to_string(),
+                options: ExportOptions {
+                    scale: Some(4.0),
+                    region: Some((Coords { x: 0, y: -1 }, Coords { x: 8, y: 4 })),
+                    ..Default::default()
+                },
+            })
+        );
+        assert_eq!(
+            "import adder.blif".parse(),
+            Ok(Command::Import("adder.blif".to_string()))
+        );
+        assert!("save".parse::<Command>().is_err());
+        assert!("equiv".parse::<Command>().is_err());
+        assert!("export adder.dot grouped".parse::<Command>().is_err());
+        assert!("export adder.png scale 0".parse::<Command>().is_err());
+        assert!(matches!(
+            "export adder.svg scale 0.5".parse(),
+            Ok(Command::Export {
+                options: ExportOptions {
+                    scale: Some(0.5),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!("export adder.png region 0 0 1".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_record() {
+        assert_eq!(
+            "record start".parse(),
+            Ok(Command::Record(Recording::Start(Vec::new())))
+        );
+        assert_eq!(
+            "record start a y".parse(),
+            Ok(Command::Record(Recording::Start(names("a y"))))
+        );
+        assert_eq!(
+            "record stop".parse(),
+            Ok(Command::Record(Recording::Stop(
+                Command::DEFAULT_RECORDING_PATH.to_string()
+            )))
+        );
+        assert_eq!(
+            "record stop adder.vcd".parse(),
+            Ok(Command::Record(Recording::Stop("adder.vcd".to_string())))
+        );
+        assert!("record".parse::<Command>().is_err());
+        assert!("record stop a.vcd b.vcd".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_probe() {
+        assert_eq!("probe a y".parse(), Ok(Command::Probe(names("a y"))));
+        assert!("probe".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_test() {
+        assert_eq!(
+            "test adder.tv".parse(),
+            Ok(Command::Test("adder.tv".to_string()))
+        );
+        assert!("test".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_script() {
+        assert_eq!(
+            "script adder.rhai".parse(),
+            Ok(Command::Script("adder.rhai".to_string()))
+        );
+        assert!("script a.rhai b.rhai".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!("route".parse(), Ok(Command::Route { all: false }));
+        assert_eq!("route all".parse(), Ok(Command::Route { all: true }));
+        assert!("route some".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown() {
+        assert!("frobnicate".parse::<Command>().is_err());
+    }
+}
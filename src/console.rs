@@ -105,6 +105,8 @@ impl ConsoleEntry {
 pub struct Console {
     entries: Vec<ConsoleEntry>,
     start_entry: usize,
+    /// Text typed into the command prompt so far, or `None` while the prompt is closed.
+    prompt: Option<String>,
 }
 
 impl Console {
@@ -119,6 +121,7 @@ impl Console {
         Self {
             entries: Vec::new(),
             start_entry: 0,
+            prompt: None,
         }
     }
 
@@ -134,6 +137,7 @@ impl Console {
             .collect()
     }
 
+    #[allow(dead_code)]
     fn visible_entries(&self) -> Vec<&ConsoleEntry> {
         let mut lines_added: usize = 0;
         self.entries
@@ -154,8 +158,73 @@ impl Console {
             entry.clear_dups_changed();
             y += entry.height();
         }
+
+        if let Some(prompt) = &self.prompt {
+            d.draw_text(
+                &format!("> {prompt}_"),
+                Self::INSET_X,
+                Self::INSET_Y + Self::VISIBLE_HEIGHT,
+                ConsoleEntry::FONT_SIZE,
+                Color::WHITE,
+            );
+        }
+    }
+
+    pub fn is_prompt_open(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    pub fn open_prompt(&mut self) {
+        self.prompt = Some(String::new());
+    }
+
+    fn type_char(&mut self, ch: char) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.push(ch);
+        }
+    }
+
+    fn backspace(&mut self) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.pop();
+        }
+    }
+
+    /// Closes the prompt, echoing the command into the log.
+    fn submit_prompt(&mut self) -> Option<String> {
+        let command = self.prompt.take()?;
+        if command.trim().is_empty() {
+            return None;
+        }
+        self.log(format!("> {command}"));
+        Some(command)
     }
 
+    /// Handles typing into the open prompt.
+    /// Returns the command once it has been submitted with enter.
+    pub fn update_prompt(&mut self, rl: &mut RaylibHandle) -> Option<String> {
+        loop {
+            // SAFETY: Only reads from raylib's input queue, which the handle guarantees is initialized.
+            let codepoint = unsafe { raylib::ffi::GetCharPressed() };
+            match char::from_u32(codepoint as u32) {
+                Some('\0') | None => break,
+                Some(ch) => self.type_char(ch),
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            self.backspace();
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            self.prompt = None;
+        } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            return self.submit_prompt();
+        }
+        None
+    }
+
+    #[allow(dead_code)]
     pub fn scroll_up(&mut self) {
         self.start_entry = self.start_entry.saturating_sub(1);
     }
@@ -280,4 +349,23 @@ mod tests {
         assert_eq!(console.entries.len(), 2);
         assert_eq!(console.entries.last().unwrap().dups, 0);
     }
+
+    #[test]
+    fn test_prompt() {
+        let mut console = Console::new();
+
+        console.type_char('x');
+        assert!(!console.is_prompt_open());
+
+        console.open_prompt();
+        for ch in "truth a b".chars() {
+            console.type_char(ch);
+        }
+        console.backspace();
+        console.type_char('c');
+
+        assert_eq!(console.submit_prompt().as_deref(), Some("truth a c"));
+        assert!(!console.is_prompt_open());
+        assert_eq!(console.entries.last().unwrap().text, "> truth a c");
+    }
 }
@@ -1,9 +1,13 @@
 use raylib::prelude::*;
 
-use crate::coords::Coords;
+use electron_architect_core::coords::Coords;
+
+use crate::tool::Tool;
 
 pub struct Cursor {
+    /// Position on screen.
     pub pos: Vector2,
+    /// Cell in the world, wherever the view has been panned to.
     pub coords: Coords,
 }
 
@@ -15,9 +19,9 @@ impl Cursor {
         }
     }
 
-    pub fn update(&mut self, rl: &RaylibHandle) {
+    pub fn update(&mut self, rl: &RaylibHandle, camera: &Camera2D) {
         self.pos = rl.get_mouse_position();
-        self.coords = Coords::from_position(self.pos);
+        self.coords = Coords::from_position(rl.get_screen_to_world2D(self.pos, camera));
     }
 
     const WIDTH: f32 = 3.0;
@@ -32,8 +36,27 @@ impl Cursor {
         y: Cursor::WIDTH / 2.0,
     };
 
-    pub fn draw(&self, d: &mut RaylibDrawHandle) {
-        d.begin_blend_mode(BlendMode::BLEND_SUBTRACT_COLORS)
-            .draw_rectangle_v(self.pos - Cursor::EXTENT, Cursor::SIZE, Color::WHITE);
+    /// How far the outline drawn for some tools reaches from the cursor.
+    const REACH: f32 = 6.0;
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle, tool: Tool) {
+        let mut d = d.begin_blend_mode(BlendMode::BLEND_SUBTRACT_COLORS);
+        let (x, y) = (self.pos.x as i32, self.pos.y as i32);
+        match tool {
+            Tool::Place => {}
+            Tool::Wire => {
+                let horz = Vector2::new(Cursor::REACH, 0.0);
+                let vert = Vector2::new(0.0, Cursor::REACH);
+                d.draw_line_v(self.pos - horz, self.pos + horz, Color::WHITE);
+                d.draw_line_v(self.pos - vert, self.pos + vert, Color::WHITE);
+            }
+            Tool::Select => {
+                let reach = Cursor::REACH as i32;
+                d.draw_rectangle_lines(x - reach, y - reach, 2 * reach, 2 * reach, Color::WHITE);
+            }
+            Tool::Pan => d.draw_circle_lines(x, y, Cursor::REACH, Color::WHITE),
+            Tool::Probe => d.draw_circle_lines(x, y, Cursor::REACH / 2.0, Color::WHITE),
+        }
+        d.draw_rectangle_v(self.pos - Cursor::EXTENT, Cursor::SIZE, Color::WHITE);
     }
 }
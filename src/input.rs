@@ -7,12 +7,24 @@ use raylib::{
 
 #[derive(Hash, PartialEq, Eq, Debug)]
 pub enum Input {
-    CreateNode,
+    UseTool,
     DestroyHovered,
     IncrementGate,
     DecrementGate,
+    IncrementGateInFamily,
+    DecrementGateInFamily,
     IncrementElbow,
     DecrementElbow,
+    ToggleAutoRoute,
+    OpenPrompt,
+    /// Ends the chain of placed nodes, or whatever else the tool is partway through.
+    Cancel,
+    Scrub,
+    PlaceTool,
+    WireTool,
+    SelectTool,
+    PanTool,
+    ProbeTool,
 }
 
 enum ScrollDirection {
@@ -36,10 +48,20 @@ impl Input {
     const fn default_binding(&self) -> KeyBind {
         use {Input::*, KeyBind::*};
         match *self {
-            CreateNode => Btn(MouseButton::MOUSE_LEFT_BUTTON),
+            UseTool | Scrub => Btn(MouseButton::MOUSE_LEFT_BUTTON),
             DestroyHovered => Btn(MouseButton::MOUSE_RIGHT_BUTTON),
             IncrementGate | IncrementElbow => Whl(ScrollDirection::Positive),
             DecrementGate | DecrementElbow => Whl(ScrollDirection::Negative),
+            IncrementGateInFamily => Key(KeyboardKey::KEY_RIGHT_BRACKET),
+            DecrementGateInFamily => Key(KeyboardKey::KEY_LEFT_BRACKET),
+            ToggleAutoRoute => Key(KeyboardKey::KEY_A),
+            OpenPrompt => Key(KeyboardKey::KEY_ENTER),
+            Cancel => Key(KeyboardKey::KEY_ESCAPE),
+            PlaceTool => Key(KeyboardKey::KEY_N),
+            WireTool => Key(KeyboardKey::KEY_W),
+            SelectTool => Key(KeyboardKey::KEY_V),
+            PanTool => Key(KeyboardKey::KEY_H),
+            ProbeTool => Key(KeyboardKey::KEY_P),
         }
     }
 }
@@ -61,12 +83,23 @@ impl InputHandler {
         use Input::*;
         Self {
             bindings: HashMap::from([
-                input_bind_default_entry!(CreateNode),
+                input_bind_default_entry!(UseTool),
                 input_bind_default_entry!(DestroyHovered),
                 input_bind_default_entry!(IncrementGate),
                 input_bind_default_entry!(DecrementGate),
+                input_bind_default_entry!(IncrementGateInFamily),
+                input_bind_default_entry!(DecrementGateInFamily),
                 input_bind_default_entry!(IncrementElbow),
                 input_bind_default_entry!(DecrementElbow),
+                input_bind_default_entry!(ToggleAutoRoute),
+                input_bind_default_entry!(OpenPrompt),
+                input_bind_default_entry!(Cancel),
+                input_bind_default_entry!(Scrub),
+                input_bind_default_entry!(PlaceTool),
+                input_bind_default_entry!(WireTool),
+                input_bind_default_entry!(SelectTool),
+                input_bind_default_entry!(PanTool),
+                input_bind_default_entry!(ProbeTool),
             ]),
         }
     }
@@ -85,7 +118,7 @@ impl InputHandler {
     /// let (mut rl, thread) = raylib::init().size(640, 480).title("Test").build();
     /// let handler = InputHandler::new();
     ///
-    /// let pressed: bool = handler.is_pressed(&rl, &Input::CreateNode);
+    /// let pressed: bool = handler.is_pressed(&rl, &Input::UseTool);
     /// ```
     pub fn is_pressed(&self, rl: &RaylibHandle, id: &Input) -> bool {
         use KeyBind::*;
@@ -97,7 +130,7 @@ impl InputHandler {
         }
     }
 
-    pub fn _is_down(&self, rl: &RaylibHandle, id: &Input) -> bool {
+    pub fn is_down(&self, rl: &RaylibHandle, id: &Input) -> bool {
         use KeyBind::*;
         match self.bindings.get(id) {
             Some(Whl(dir)) => InputHandler::is_scrolled(rl, dir),
@@ -1,36 +1,60 @@
 use raylib::prelude::*;
-mod common_traits;
+mod command;
 mod console;
-mod coords;
 mod cursor;
-mod graph;
 mod input;
+mod palette;
+mod script;
+mod tool;
+mod waveform;
 
 #[allow(unused_imports)]
 use {
-    common_traits::Scrollable,
+    command::Command,
     console::Console,
-    coords::Coords,
     cursor::Cursor,
-    graph::{
-        elbow::Elbow,
-        gate::{Gate, Gate0, Gate1, GateN},
-        node::Node,
-        wire::Wire,
-        Graph,
+    electron_architect_core::{
+        common_traits::Scrollable,
+        coords::Coords,
+        graph::{
+            elbow::Elbow,
+            gate::{Gate, Gate0, Gate1, GateN},
+            loops::CombinationalLoop,
+            node::{Node, NodeId},
+            sim::Simulation,
+            wire::{Wire, WireId},
+            Graph,
+        },
     },
     input::{Input, InputHandler},
+    palette::GatePalette,
+    tool::{Tool, Tools},
+    waveform::WaveformPanel,
 };
 
-fn draw_grid(d: &mut RaylibDrawHandle, width: i32, height: i32) {
-    for x in (0..width).step_by(Coords::GRID_SIZE as usize) {
-        d.draw_line(x, 0, x, height, Color::DARKGRAY);
+/// Draws the lines of the grid within view of the camera.
+fn draw_grid(d: &mut impl RaylibDraw, camera: &Camera2D, width: i32, height: i32) {
+    let size = Coords::GRID_SIZE as i32;
+    let left = (camera.target.x as i32).div_euclid(size) * size;
+    let top = (camera.target.y as i32).div_euclid(size) * size;
+    let (right, bottom) = (left + width + size, top + height + size);
+    for x in (left..right).step_by(size as usize) {
+        d.draw_line(x, top, x, bottom, Color::DARKGRAY);
     }
-    for y in (0..height).step_by(Coords::GRID_SIZE as usize) {
-        d.draw_line(0, y, width, y, Color::DARKGRAY);
+    for y in (top..bottom).step_by(size as usize) {
+        d.draw_line(left, y, right, y, Color::DARKGRAY);
     }
 }
 
+fn describe_loop(graph: &Graph, combinational_loop: &CombinationalLoop) -> String {
+    let coords: Vec<String> = combinational_loop
+        .nodes
+        .iter()
+        .map(|&id| graph.node(id).coords.to_string())
+        .collect();
+    format!("Combinational loop through {}", coords.join(" "))
+}
+
 fn main() {
     let window_width: i32 = 1280;
     let window_height: i32 = 720;
@@ -43,24 +67,47 @@ fn main() {
 
     rl.set_target_fps(244);
     rl.hide_cursor();
+    rl.set_exit_key(None); // Escape is used to close the prompt and end chains
 
     let input = InputHandler::new();
     let mut console = Console::new();
     let mut graph = Graph::new();
-    let mut current_gate = Gate::G1(Gate1::Buffer);
-    let mut current_elbow = Elbow::HorzS;
+    let mut simulation = Simulation::new();
+    let mut waveform = WaveformPanel::new();
     let mut cursor: Cursor = Cursor::new();
 
-    let mut hovered_node: Option<&Node> = None;
-    let mut hovered_wire: Option<&Wire> = None;
-    let mut current_node: Option<&Node> = None;
+    let mut hovered_node: Option<NodeId>;
+    let mut hovered_wire: Option<WireId>;
+    let mut tools = Tools::new();
+    let mut loop_wires: Vec<WireId>;
 
     console.log("Hello world!");
+    if let Err(e) = electron_architect_core::plugins::register_all() {
+        console.err(e);
+    }
+    let palette = GatePalette::new();
+
+    // Scripts named on the command line run before the first frame
+    for path in std::env::args().skip(1) {
+        Command::Script(path).execute(
+            &mut graph,
+            &mut simulation,
+            &mut console,
+            &cursor,
+            &tools.selection,
+        );
+    }
+    // As after any command, since the scripts may have built loops
+    loop_wires = graph
+        .find_combinational_loops()
+        .into_iter()
+        .flat_map(|l| l.wires)
+        .collect();
 
     while !rl.window_should_close() {
         // Tick
 
-        cursor.update(&rl);
+        cursor.update(&rl, &tools.camera);
 
         hovered_node = graph.find_node_at_coords(&cursor.coords);
         hovered_wire = match hovered_node {
@@ -68,30 +115,109 @@ fn main() {
             Some(_) => None,
         };
 
-        if input.is_pressed(&rl, &Input::CreateNode) {
-            let new_node = graph.add_node(&current_gate, &cursor.coords);
+        let panel_bounds =
+            WaveformPanel::bounding_box(simulation.probes.len(), window_width, window_height);
+        let over_panel = waveform.update(&rl, &input, &cursor, &panel_bounds);
+        let palette_bounds = palette.bounding_box(window_width);
+        let over_palette = palette.update(
+            &rl,
+            &input,
+            &cursor,
+            &palette_bounds,
+            &mut tools.current_gate,
+        );
+
+        if !over_panel && !over_palette {
+            let new_wire = tools.update(
+                &rl,
+                &input,
+                &cursor,
+                &mut graph,
+                &mut simulation,
+                &mut console,
+            );
+
+            if let Some(new_wire) = new_wire {
+                let loops = graph.find_combinational_loops();
+                for combinational_loop in &loops {
+                    if combinational_loop.wires.contains(&new_wire) {
+                        console.warn(describe_loop(&graph, combinational_loop));
+                    }
+                }
+                loop_wires = loops.into_iter().flat_map(|l| l.wires).collect();
+            }
+        } else {
+            tools.update_over_panel(&rl, &input);
+        }
 
-            // Chain nodes
-            if current_node.is_some() {
-                graph.add_wire(current_node.unwrap(), new_node, &current_elbow);
+        if console.is_prompt_open() {
+            if let Some(text) = console.update_prompt(&mut rl) {
+                match text.parse::<Command>() {
+                    Ok(command) => command.execute(
+                        &mut graph,
+                        &mut simulation,
+                        &mut console,
+                        &cursor,
+                        &tools.selection,
+                    ),
+                    Err(e) => console.err(e),
+                }
+                // Commands may replace the graph entirely, so start afresh
+                tools.reset();
+                waveform.stop_scrubbing();
+                loop_wires = graph
+                    .find_combinational_loops()
+                    .into_iter()
+                    .flat_map(|l| l.wires)
+                    .collect();
             }
-            current_node = Some(new_node);
-            console.log(format!("Created node at {}", cursor.coords));
+        } else if input.is_pressed(&rl, &Input::OpenPrompt) {
+            console.open_prompt();
+        } else if input.is_pressed(&rl, &Input::Cancel) {
+            tools.cancel();
+        } else if input.is_pressed(&rl, &Input::ToggleAutoRoute) {
+            tools.auto_route = !tools.auto_route;
+            match tools.auto_route {
+                true => console.log("Routing new wires around whatever's in the way"),
+                false => console.log("Shaping new wires with the current elbow"),
+            }
+        } else if let Some(gate) = palette.shortcut(&rl) {
+            tools.current_gate = gate;
+        } else if let Some(&tool) = Tool::ALL
+            .iter()
+            .find(|tool| input.is_pressed(&rl, &tool.input()))
+        {
+            tools.switch(tool);
         }
 
         if input.is_pressed(&rl, &Input::IncrementGate) {
-            current_gate.incr();
+            tools.current_gate.incr();
         } else if input.is_pressed(&rl, &Input::DecrementGate) {
-            current_gate.decr();
+            tools.current_gate.decr();
+        }
+
+        // Only while the prompt is closed, as the keys are also typed into it
+        if !console.is_prompt_open() {
+            if input.is_pressed(&rl, &Input::IncrementGateInFamily) {
+                tools.current_gate = tools.current_gate.next_in_family();
+            } else if input.is_pressed(&rl, &Input::DecrementGateInFamily) {
+                tools.current_gate = tools.current_gate.prev_in_family();
+            }
         }
 
         if input.is_pressed(&rl, &Input::IncrementElbow) {
-            current_elbow.incr();
+            tools.current_elbow.incr();
         } else if input.is_pressed(&rl, &Input::DecrementElbow) {
-            current_elbow.decr();
+            tools.current_elbow.decr();
+        }
+
+        // Held while looking back through the waveform
+        if !waveform.is_scrubbing() {
+            simulation.step(&graph);
+            waveform.sample(&simulation);
         }
 
-        if (console.bounding_box()).check_collision_point_rec(&cursor.pos) {
+        if (console.bounding_box()).check_collision_point_rec(cursor.pos) {
             console.log("Hovering console");
         }
 
@@ -100,15 +226,29 @@ fn main() {
             let mut d = rl.begin_drawing(&thread);
             d.clear_background(Color::BLACK);
 
-            draw_grid(&mut d, window_width, window_height);
+            {
+                let mut world = d.begin_mode2D(tools.camera);
+                draw_grid(&mut world, &tools.camera, window_width, window_height);
+
+                graph.draw(&mut world);
+                graph.draw_highlighted_wires(&mut world, &loop_wires, Color::RED);
+                if let Some(id) = hovered_wire {
+                    graph.draw_highlighted_wires(&mut world, &[id], Color::LIGHTGRAY);
+                }
 
-            graph.draw(&mut d);
+                if let Some((_, state)) = waveform.scrubbed_state() {
+                    graph.draw_state(&mut world, state, Color::LIME);
+                }
+
+                tools.draw(&mut world, &graph, &cursor);
+            }
 
-            current_gate.draw(&mut d, &cursor.coords, Color::BLUE);
+            waveform.draw(&mut d, &graph, &simulation.probes, &panel_bounds);
+            palette.draw(&mut d, &palette_bounds, &tools);
 
             console.draw(&mut d);
 
-            cursor.draw(&mut d);
+            cursor.draw(&mut d, tools.tool);
         }
     }
 }
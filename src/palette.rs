@@ -0,0 +1,186 @@
+use raylib::prelude::*;
+
+use electron_architect_core::graph::gate::Gate;
+
+use crate::{
+    cursor::Cursor,
+    input::{Input, InputHandler},
+    tool::Tools,
+};
+
+/// Docked along the right of the window, listing every gate to pick from.
+pub struct GatePalette {
+    gates: Vec<Gate>,
+}
+
+impl GatePalette {
+    const WIDTH: i32 = 120;
+    const PADDING: i32 = 6;
+    const ROW_HEIGHT: i32 = 18;
+    /// Room for the current tool, gate and elbow above the list.
+    const HEADER_HEIGHT: i32 = 3 * Self::ROW_HEIGHT;
+    const FONT_SIZE: i32 = 8;
+
+    /// Number keys select the first ten gates, in the order they're listed.
+    const SHORTCUTS: [KeyboardKey; 10] = [
+        KeyboardKey::KEY_ONE,
+        KeyboardKey::KEY_TWO,
+        KeyboardKey::KEY_THREE,
+        KeyboardKey::KEY_FOUR,
+        KeyboardKey::KEY_FIVE,
+        KeyboardKey::KEY_SIX,
+        KeyboardKey::KEY_SEVEN,
+        KeyboardKey::KEY_EIGHT,
+        KeyboardKey::KEY_NINE,
+        KeyboardKey::KEY_ZERO,
+    ];
+
+    /// Call once gate plugins have been registered, so they're listed too.
+    pub fn new() -> Self {
+        Self { gates: Gate::all() }
+    }
+
+    pub fn bounding_box(&self, window_width: i32) -> Rectangle {
+        Rectangle {
+            x: (window_width - Self::WIDTH) as f32,
+            y: 0.0,
+            width: Self::WIDTH as f32,
+            height: (Self::HEADER_HEIGHT
+                + self.gates.len() as i32 * Self::ROW_HEIGHT
+                + 2 * Self::PADDING) as f32,
+        }
+    }
+
+    fn row_top(i: usize, bounds: &Rectangle) -> i32 {
+        bounds.y as i32 + Self::PADDING + Self::HEADER_HEIGHT + i as i32 * Self::ROW_HEIGHT
+    }
+
+    fn gate_at(&self, pos: Vector2, bounds: &Rectangle) -> Option<Gate> {
+        if pos.x < bounds.x || pos.x >= bounds.x + bounds.width {
+            return None;
+        }
+        let offset = pos.y as i32 - Self::row_top(0, bounds);
+        if offset < 0 {
+            return None;
+        }
+        self.gates
+            .get((offset / Self::ROW_HEIGHT) as usize)
+            .copied()
+    }
+
+    fn shortcut_label(i: usize) -> Option<String> {
+        (i < Self::SHORTCUTS.len()).then(|| ((i + 1) % 10).to_string())
+    }
+
+    /// Gate whose number key was pressed. Only call while the prompt is closed.
+    pub fn shortcut(&self, rl: &RaylibHandle) -> Option<Gate> {
+        Self::SHORTCUTS
+            .iter()
+            .zip(&self.gates)
+            .find(|(&key, _)| rl.is_key_pressed(key))
+            .map(|(_, &gate)| gate)
+    }
+
+    /// Clicking a row selects its gate.
+    /// Returns whether the cursor is over the palette, in which case the editor should ignore it.
+    pub fn update(
+        &self,
+        rl: &RaylibHandle,
+        input: &InputHandler,
+        cursor: &Cursor,
+        bounds: &Rectangle,
+        current_gate: &mut Gate,
+    ) -> bool {
+        if !bounds.check_collision_point_rec(cursor.pos) {
+            return false;
+        }
+        if input.is_pressed(rl, &Input::UseTool) {
+            if let Some(gate) = self.gate_at(cursor.pos, bounds) {
+                *current_gate = gate;
+            }
+        }
+        true
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle, bounds: &Rectangle, tools: &Tools) {
+        d.draw_rectangle_rec(bounds, Color::BLACK.fade(0.9));
+        d.draw_line(
+            bounds.x as i32,
+            bounds.y as i32,
+            bounds.x as i32,
+            (bounds.y + bounds.height) as i32,
+            Color::DARKGRAY,
+        );
+
+        let left = bounds.x as i32 + Self::PADDING;
+        let top = bounds.y as i32 + Self::PADDING;
+        let header = [
+            format!("{} tool", tools.tool.name()),
+            tools.current_gate.to_string(),
+            match tools.auto_route {
+                true => "Elbow auto".to_string(),
+                false => format!("Elbow {}", tools.current_elbow.name()),
+            },
+        ];
+        for (i, line) in header.iter().enumerate() {
+            let y = top + i as i32 * Self::ROW_HEIGHT;
+            d.draw_text(line, left, y, Self::FONT_SIZE, Color::WHITE);
+        }
+
+        for (i, gate) in self.gates.iter().enumerate() {
+            let row_top = Self::row_top(i, bounds);
+            let text_y = row_top + (Self::ROW_HEIGHT - Self::FONT_SIZE) / 2;
+            let color = match *gate == tools.current_gate {
+                true => Color::BLUE,
+                false => Color::GRAY,
+            };
+            if let Some(label) = Self::shortcut_label(i) {
+                d.draw_text(&label, left, text_y, Self::FONT_SIZE, Color::DARKGRAY);
+            }
+            let center = Vector2::new((left + 20) as f32, (row_top + Self::ROW_HEIGHT / 2) as f32);
+            gate.draw_v(d, &center, color);
+            let glyph_width = measure_text(gate.glyph(), Self::FONT_SIZE);
+            d.draw_text(
+                gate.glyph(),
+                center.x as i32 - glyph_width / 2,
+                text_y,
+                Self::FONT_SIZE,
+                Color::BLACK,
+            );
+            d.draw_text(gate.name(), left + 34, text_y, Self::FONT_SIZE, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use electron_architect_core::graph::gate::{Gate0, GateN};
+
+    #[test]
+    fn test_gate_at() {
+        let palette = GatePalette::new();
+        let bounds = palette.bounding_box(1280);
+        assert_eq!(bounds.x, 1160.0);
+
+        let row = |i: usize| Vector2::new(1200.0, GatePalette::row_top(i, &bounds) as f32 + 1.0);
+        assert_eq!(
+            palette.gate_at(row(0), &bounds),
+            Some(Gate::G0(Gate0::Never))
+        );
+        assert_eq!(palette.gate_at(row(4), &bounds), Some(Gate::GN(GateN::And)));
+        // The header isn't a gate, and neither is anywhere off the palette
+        assert_eq!(palette.gate_at(Vector2::new(1200.0, 10.0), &bounds), None);
+        assert_eq!(
+            palette.gate_at(Vector2::new(100.0, row(0).y), &bounds),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shortcut_labels() {
+        assert_eq!(GatePalette::shortcut_label(0).as_deref(), Some("1"));
+        assert_eq!(GatePalette::shortcut_label(9).as_deref(), Some("0"));
+        assert_eq!(GatePalette::shortcut_label(10), None);
+    }
+}
@@ -0,0 +1,325 @@
+//! Rhai scripts for building circuits procedurally and automating the simulator.
+//!
+//! ```text
+//! let a = node(0, 0, "buffer", "a");
+//! let y = node(3, 0, "nor");
+//! wire(a, y);
+//! drive(a, true);
+//! step(2);
+//! print(`y is ${output(y)}`);
+//! ```
+//!
+//! Nodes and wires are referred to by the numbers `node` and `wire` return.
+
+use std::{cell::RefCell, fs, rc::Rc};
+
+use electron_architect_core::{
+    coords::Coords,
+    graph::{
+        elbow::Elbow,
+        gate::Gate,
+        node::{Node, NodeId},
+        sim::Simulation,
+        synth::choose_elbow,
+        Graph,
+    },
+};
+use rhai::{Engine, EvalAltResult};
+
+use crate::console::Console;
+
+/// Stops runaway loops from freezing the editor.
+const MAX_OPERATIONS: u64 = 50_000_000;
+/// Most ticks a script can step the simulation, which the operation limit doesn't count.
+const MAX_TICKS: u64 = 1_000_000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+enum Message {
+    Log(String),
+    Warn(String),
+}
+
+/// Everything a script can touch, shared between the functions registered with the engine.
+struct Context {
+    graph: Graph,
+    simulation: Simulation,
+    messages: Vec<Message>,
+    /// Of [`MAX_TICKS`].
+    ticks_left: u64,
+}
+
+impl Context {
+    fn node_id(&self, id: i64) -> ScriptResult<NodeId> {
+        usize::try_from(id)
+            .ok()
+            .filter(|&id| id < self.graph.nodes().count())
+            .map(NodeId)
+            .ok_or_else(|| format!("No node {id}").into())
+    }
+
+    fn add_node(&mut self, x: i64, y: i64, gate: &str) -> ScriptResult<i64> {
+        let coords = Coords {
+            x: i32::try_from(x).map_err(|_| format!("x coordinate {x} is out of range"))?,
+            y: i32::try_from(y).map_err(|_| format!("y coordinate {y} is out of range"))?,
+        };
+        let gate = Gate::from_name(gate).ok_or_else(|| format!("Unknown gate `{gate}`"))?;
+        if self.graph.find_node_at_coords(&coords).is_some() {
+            return Err(format!("There's already a node at {coords}").into());
+        }
+        Ok(self.graph.add_node(&gate, &coords).0 as i64)
+    }
+
+    fn add_wire(&mut self, from: i64, to: i64, elbow: Option<&str>) -> ScriptResult<i64> {
+        let (from, to) = (self.node_id(from)?, self.node_id(to)?);
+        self.graph.check_wire(from, to)?;
+        let elbow = match elbow {
+            Some(name) => {
+                Elbow::from_name(name).ok_or_else(|| format!("Unknown elbow `{name}`"))?
+            }
+            None => choose_elbow(&self.graph.node(from).coords, &self.graph.node(to).coords),
+        };
+        Ok(self.graph.add_wire(from, to, &elbow).0 as i64)
+    }
+
+    fn set_name(&mut self, id: i64, name: &str) -> ScriptResult<()> {
+        let id = self.node_id(id)?;
+        check_name(name)?;
+        if self
+            .graph
+            .find_node_by_name(name)
+            .is_some_and(|other| other != id)
+        {
+            return Err(format!("Another node is already named `{name}`").into());
+        }
+        self.graph.node_mut(id).name = Some(name.to_string());
+        Ok(())
+    }
+
+    fn step(&mut self, ticks: i64) -> ScriptResult<()> {
+        let ticks = u64::try_from(ticks).unwrap_or(0);
+        if ticks > self.ticks_left {
+            return Err(format!("Scripts can't step more than {MAX_TICKS} ticks in all").into());
+        }
+        self.ticks_left -= ticks;
+        for _ in 0..ticks {
+            self.simulation.step(&self.graph);
+        }
+        Ok(())
+    }
+}
+
+/// Scripts can pass any string, but a name has to survive being saved and loaded.
+fn check_name(name: &str) -> ScriptResult<()> {
+    match Node::is_valid_name(name) {
+        true => Ok(()),
+        false => Err(format!("`{name}` can't be a name, as names are one word").into()),
+    }
+}
+
+fn build_engine(context: &Rc<RefCell<Context>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let ctx = context.clone();
+    engine.on_print(move |text| {
+        ctx.borrow_mut()
+            .messages
+            .push(Message::Log(text.to_string()))
+    });
+    let ctx = context.clone();
+    engine.on_debug(move |text, _, _| {
+        ctx.borrow_mut()
+            .messages
+            .push(Message::Log(text.to_string()))
+    });
+    let ctx = context.clone();
+    engine.register_fn("warn", move |text: &str| {
+        ctx.borrow_mut()
+            .messages
+            .push(Message::Warn(text.to_string()))
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("node", move |x: i64, y: i64, gate: &str| {
+        ctx.borrow_mut().add_node(x, y, gate)
+    });
+    let ctx = context.clone();
+    engine.register_fn(
+        "node",
+        move |x: i64, y: i64, gate: &str, name: &str| -> ScriptResult<i64> {
+            let mut ctx = ctx.borrow_mut();
+            // Before adding the node, so a bad name doesn't leave it behind unnamed
+            check_name(name)?;
+            let id = ctx.add_node(x, y, gate)?;
+            ctx.set_name(id, name)?;
+            Ok(id)
+        },
+    );
+    let ctx = context.clone();
+    engine.register_fn("wire", move |from: i64, to: i64| {
+        ctx.borrow_mut().add_wire(from, to, None)
+    });
+    let ctx = context.clone();
+    engine.register_fn("wire", move |from: i64, to: i64, elbow: &str| {
+        ctx.borrow_mut().add_wire(from, to, Some(elbow))
+    });
+    let ctx = context.clone();
+    engine.register_fn("set_gate", move |id: i64, gate: &str| -> ScriptResult<()> {
+        let mut ctx = ctx.borrow_mut();
+        let id = ctx.node_id(id)?;
+        ctx.graph.node_mut(id).gate =
+            Gate::from_name(gate).ok_or_else(|| format!("Unknown gate `{gate}`"))?;
+        Ok(())
+    });
+    let ctx = context.clone();
+    engine.register_fn("set_name", move |id: i64, name: &str| {
+        ctx.borrow_mut().set_name(id, name)
+    });
+    let ctx = context.clone();
+    engine.register_fn("find", move |name: &str| -> ScriptResult<i64> {
+        match ctx.borrow().graph.find_node_by_name(name) {
+            Some(id) => Ok(id.0 as i64),
+            None => Err(format!("No node named `{name}`").into()),
+        }
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("drive", move |id: i64, value: bool| -> ScriptResult<()> {
+        let mut ctx = ctx.borrow_mut();
+        let id = ctx.node_id(id)?;
+        ctx.simulation.drive(id, value);
+        Ok(())
+    });
+    let ctx = context.clone();
+    engine.register_fn("step", move || ctx.borrow_mut().step(1));
+    let ctx = context.clone();
+    engine.register_fn("step", move |ticks: i64| ctx.borrow_mut().step(ticks));
+    let ctx = context.clone();
+    engine.register_fn("output", move |id: i64| -> ScriptResult<bool> {
+        let ctx = ctx.borrow();
+        Ok(ctx.simulation.output(ctx.node_id(id)?))
+    });
+    let ctx = context.clone();
+    engine.register_fn("tick", move || ctx.borrow().simulation.tick() as i64);
+
+    engine
+}
+
+/// Runs `source` against the graph and simulation, logging what it prints to the console.
+/// Anything the script did before an error is kept.
+pub fn run(
+    source: &str,
+    graph: &mut Graph,
+    simulation: &mut Simulation,
+    console: &mut Console,
+) -> Result<(), String> {
+    let context = Rc::new(RefCell::new(Context {
+        graph: std::mem::take(graph),
+        simulation: std::mem::take(simulation),
+        messages: Vec::new(),
+        ticks_left: MAX_TICKS,
+    }));
+    let result = build_engine(&context).run(source);
+
+    let context = Rc::into_inner(context)
+        .expect("The engine is dropped once the script finishes")
+        .into_inner();
+    *graph = context.graph;
+    *simulation = context.simulation;
+    for message in context.messages {
+        match message {
+            Message::Log(text) => console.log(text),
+            Message::Warn(text) => console.warn(text),
+        }
+    }
+    result.map_err(|e| format!("Script error: {e}"))
+}
+
+pub fn run_file(
+    path: &str,
+    graph: &mut Graph,
+    simulation: &mut Simulation,
+    console: &mut Console,
+) -> Result<(), String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("Couldn't read `{path}`: {e}"))?;
+    run(&source, graph, simulation, console).map_err(|e| format!("`{path}`: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_script(source: &str) -> (Result<(), String>, Graph, Simulation) {
+        let mut graph = Graph::new();
+        let mut simulation = Simulation::new();
+        let result = run(source, &mut graph, &mut simulation, &mut Console::new());
+        (result, graph, simulation)
+    }
+
+    #[test]
+    fn test_build_and_simulate() {
+        let (result, graph, simulation) = run_script(
+            r#"
+            let a = node(0, 0, "buffer", "a");
+            let b = node(0, 2, "buffer", "b");
+            let y = node(3, 1, "and", "y");
+            wire(a, y);
+            wire(b, y, "horz_s");
+            drive(a, true);
+            drive(b, true);
+            step(2);
+            if !output(y) { throw "and of two ones should be one"; }
+            "#,
+        );
+        result.unwrap();
+        assert_eq!(graph.nodes().count(), 3);
+        assert_eq!(graph.wires().count(), 2);
+        assert_eq!(simulation.tick(), 2);
+        assert!(simulation.output(graph.find_node_by_name("y").unwrap()));
+    }
+
+    #[test]
+    fn test_generate_in_a_loop() {
+        let (result, graph, _) = run_script(
+            r#"
+            let prev = node(0, 0, "buffer", "in");
+            for i in 1..=8 {
+                let next = node(i * 2, 0, "nor");
+                wire(prev, next);
+                prev = next;
+            }
+            set_gate(find("in"), "always");
+            "#,
+        );
+        result.unwrap();
+        assert_eq!(graph.nodes().count(), 9);
+        assert_eq!(graph.wires().count(), 8);
+    }
+
+    #[test]
+    fn test_errors_keep_earlier_changes() {
+        let (result, graph, _) = run_script(r#"node(0, 0, "buffer"); node(0, 0, "and");"#);
+        assert!(result.unwrap_err().contains("already a node at (0, 0)"));
+        assert_eq!(graph.nodes().count(), 1);
+
+        assert!(run_script(r#"node(0, 0, "latch")"#).0.is_err());
+        assert!(run_script(r#"wire(0, 1)"#).0.is_err());
+        let (result, graph, _) =
+            run_script(r#"let a = node(0, 0, "buffer"); wire(a, node(2, 0, "always"));"#);
+        assert!(result.unwrap_err().contains("more than 0 inputs"));
+        assert_eq!(graph.wires().count(), 0);
+        assert!(run_script(r#"find("missing")"#).0.is_err());
+        let (result, graph, _) = run_script(r#"node(0, 0, "buffer", "carry out")"#);
+        assert!(result.unwrap_err().contains("one word"));
+        assert_eq!(graph.nodes().count(), 0);
+        let (result, graph, _) = run_script(r#"set_name(node(0, 0, "buffer"), "")"#);
+        assert!(result.is_err());
+        assert_eq!(graph.node(NodeId(0)).name, None);
+        assert!(run_script("loop {}").0.is_err());
+        assert!(run_script("step(1_000_000_000_000)")
+            .0
+            .unwrap_err()
+            .contains("ticks"));
+    }
+}
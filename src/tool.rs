@@ -0,0 +1,399 @@
+use std::cell::RefCell;
+
+use raylib::prelude::*;
+
+use electron_architect_core::{
+    coords::Coords,
+    graph::{
+        elbow::Elbow,
+        gate::{Gate, Gate1},
+        node::NodeId,
+        route::Route,
+        sim::Simulation,
+        wire::{Wire, WireId},
+        Graph,
+    },
+};
+
+use crate::{
+    console::Console,
+    cursor::Cursor,
+    input::{Input, InputHandler},
+};
+
+/// What clicking in the editor does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tool {
+    /// Places the current gate, wired from the last node placed.
+    Place,
+    /// Drags from one node to another that's already there to wire them.
+    Wire,
+    /// Drags out a box to select the nodes inside it.
+    Select,
+    /// Drags the view around.
+    Pan,
+    /// Adds or removes a node from the waveform panel.
+    Probe,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 5] = [
+        Tool::Place,
+        Tool::Wire,
+        Tool::Select,
+        Tool::Pan,
+        Tool::Probe,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        use Tool::*;
+        match self {
+            Place => "Place",
+            Wire => "Wire",
+            Select => "Select",
+            Pan => "Pan",
+            Probe => "Probe",
+        }
+    }
+
+    /// Input that switches to the tool.
+    pub const fn input(&self) -> Input {
+        use Tool::*;
+        match self {
+            Place => Input::PlaceTool,
+            Wire => Input::WireTool,
+            Select => Input::SelectTool,
+            Pan => Input::PanTool,
+            Probe => Input::ProbeTool,
+        }
+    }
+}
+
+/// Nodes within the box with corners at `a` and `b`, inclusive.
+fn nodes_in_box(graph: &Graph, a: &Coords, b: &Coords) -> Vec<NodeId> {
+    let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+    let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+    graph
+        .nodes()
+        .filter(|(_, node)| {
+            (min_x..=max_x).contains(&node.coords.x) && (min_y..=max_y).contains(&node.coords.y)
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Start, end and graph revision a route was found for.
+type RouteKey = (Coords, Coords, u64);
+
+/// The tool in use, and whatever each tool is partway through.
+pub struct Tools {
+    pub tool: Tool,
+    /// Gate the place tool places.
+    pub current_gate: Gate,
+    /// Shape of the wires the place and wire tools add, unless they're routed automatically.
+    pub current_elbow: Elbow,
+    /// Whether wires are routed around whatever's in the way instead of using the current elbow.
+    pub auto_route: bool,
+    /// Last node placed, which the next one is wired from.
+    pub current_node: Option<NodeId>,
+    /// Node the wire tool is being dragged from.
+    wire_from: Option<NodeId>,
+    /// Corner the selection box is being dragged from.
+    select_from: Option<Coords>,
+    pub selection: Vec<NodeId>,
+    /// Where the cursor was on screen last frame while panning.
+    pan_from: Option<Vector2>,
+    pub camera: Camera2D,
+    /// Route last previewed, so it's only searched for again once its [`RouteKey`] changes.
+    pending_route: RefCell<Option<(RouteKey, Option<Route>)>>,
+}
+
+impl Tools {
+    pub fn new() -> Self {
+        Self {
+            tool: Tool::Place,
+            current_gate: Gate::G1(Gate1::Buffer),
+            current_elbow: Elbow::HorzS,
+            auto_route: false,
+            current_node: None,
+            wire_from: None,
+            select_from: None,
+            selection: Vec::new(),
+            pan_from: None,
+            camera: Camera2D {
+                zoom: 1.0,
+                ..Camera2D::default()
+            },
+            pending_route: RefCell::new(None),
+        }
+    }
+
+    /// Forgets anything referring to nodes, for when the graph is replaced.
+    pub fn reset(&mut self) {
+        self.current_node = None;
+        self.wire_from = None;
+        self.select_from = None;
+        self.selection.clear();
+    }
+
+    /// Drops whatever the tool is partway through, ending the chain of placed nodes.
+    pub fn cancel(&mut self) {
+        self.current_node = None;
+        self.wire_from = None;
+        self.select_from = None;
+        self.pan_from = None;
+    }
+
+    /// Switches tool, dropping whatever the last one was partway through.
+    pub fn switch(&mut self, tool: Tool) {
+        self.cancel();
+        self.tool = tool;
+    }
+
+    fn add_wire(&self, graph: &mut Graph, from: NodeId, to: NodeId) -> WireId {
+        match self.auto_route {
+            true => graph.add_routed_wire(from, to),
+            false => graph.add_wire(from, to, &self.current_elbow),
+        }
+    }
+
+    /// The path a wire from `start` to `end` would take if it were added now.
+    fn draw_pending_wire(
+        &self,
+        d: &mut impl RaylibDraw,
+        graph: &Graph,
+        start: &Coords,
+        end: &Coords,
+        color: Color,
+    ) {
+        match self.auto_route {
+            true => {
+                let key = (*start, *end, graph.revision());
+                let mut cache = self.pending_route.borrow_mut();
+                if cache.as_ref().map(|(cached, _)| cached) != Some(&key) {
+                    *cache = Some((key, graph.route(start, end, None)));
+                }
+                if let Some((_, Some(route))) = cache.as_ref() {
+                    // Only the shape matters, not which nodes it's between
+                    let mut wire = Wire::new(NodeId(0), NodeId(0), &route.elbow);
+                    wire.set_route(route.clone());
+                    wire.draw(d, start, end, color);
+                }
+            }
+            false => self.current_elbow.draw(d, start, end, color),
+        }
+    }
+
+    /// Call instead of [`Tools::update`] while the cursor is over a panel.
+    /// Letting go there drops any drag, as there's nothing under the cursor to finish it on.
+    pub fn update_over_panel(&mut self, rl: &RaylibHandle, input: &InputHandler) {
+        if !input.is_down(rl, &Input::UseTool) {
+            self.drop_drags();
+        }
+    }
+
+    /// Unlike [`Tools::cancel`], the chain of placed nodes carries on.
+    fn drop_drags(&mut self) {
+        self.wire_from = None;
+        self.select_from = None;
+        self.pan_from = None;
+    }
+
+    /// Call while the cursor isn't over a panel. Returns the wire added, if any.
+    pub fn update(
+        &mut self,
+        rl: &RaylibHandle,
+        input: &InputHandler,
+        cursor: &Cursor,
+        graph: &mut Graph,
+        simulation: &mut Simulation,
+        console: &mut Console,
+    ) -> Option<WireId> {
+        let hovered_node = graph.find_node_at_coords(&cursor.coords);
+        match self.tool {
+            Tool::Place => {
+                if input.is_pressed(rl, &Input::UseTool) {
+                    // Clicking a node that's already there chains on from it instead of stacking another
+                    let new_node = match hovered_node {
+                        Some(existing) => {
+                            console.log(format!("Continuing from node at {}", cursor.coords));
+                            existing
+                        }
+                        None => {
+                            console.log(format!("Created node at {}", cursor.coords));
+                            graph.add_node(&self.current_gate, &cursor.coords)
+                        }
+                    };
+                    // Chain nodes, unless the new one can't take the wire
+                    let prev = self.current_node.filter(|&prev| prev != new_node);
+                    let new_wire = prev.and_then(|prev| match graph.check_wire(prev, new_node) {
+                        Ok(()) => Some(self.add_wire(graph, prev, new_node)),
+                        Err(e) => {
+                            console.warn(e);
+                            None
+                        }
+                    });
+                    self.current_node = Some(new_node);
+                    return new_wire;
+                }
+            }
+
+            Tool::Wire => {
+                if input.is_pressed(rl, &Input::UseTool) {
+                    self.wire_from = hovered_node;
+                } else if !input.is_down(rl, &Input::UseTool) {
+                    // Letting go anywhere but on another node drops the wire,
+                    // so that a plain click does nothing
+                    if let (Some(from), Some(to)) = (self.wire_from.take(), hovered_node) {
+                        if from == to {
+                            return None;
+                        }
+                        match graph.check_wire(from, to) {
+                            Ok(()) => return Some(self.add_wire(graph, from, to)),
+                            Err(e) => console.warn(e),
+                        }
+                    }
+                }
+            }
+
+            Tool::Select => {
+                if input.is_pressed(rl, &Input::UseTool) {
+                    self.select_from = Some(cursor.coords);
+                } else if !input.is_down(rl, &Input::UseTool) {
+                    if let Some(from) = self.select_from.take() {
+                        self.selection = nodes_in_box(graph, &from, &cursor.coords);
+                        console.log(format!("Selected {} nodes", self.selection.len()));
+                    }
+                }
+            }
+
+            Tool::Pan => match input.is_down(rl, &Input::UseTool) {
+                true => {
+                    if let Some(from) = self.pan_from {
+                        self.camera.target += from - cursor.pos;
+                    }
+                    self.pan_from = Some(cursor.pos);
+                }
+                false => self.pan_from = None,
+            },
+
+            Tool::Probe => {
+                if let (true, Some(id)) = (input.is_pressed(rl, &Input::UseTool), hovered_node) {
+                    let coords = graph.node(id).coords;
+                    match simulation.toggle_probe(id) {
+                        true => console.log(format!("Probing {coords}")),
+                        false => console.log(format!("Stopped probing {coords}")),
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Draws in world space, so call within the camera's 2D mode.
+    pub fn draw(&self, d: &mut impl RaylibDraw, graph: &Graph, cursor: &Cursor) {
+        for &id in &self.selection {
+            let node = graph.node(id);
+            node.gate.draw(d, &node.coords, Color::GOLD);
+        }
+
+        match self.tool {
+            Tool::Place => {
+                // Where the wire from the last node placed will go
+                if let Some(prev) = self.current_node {
+                    let start = graph.node(prev).coords;
+                    let ghost = Color::BLUE.fade(0.5);
+                    self.draw_pending_wire(d, graph, &start, &cursor.coords, ghost);
+                }
+                self.current_gate.draw(d, &cursor.coords, Color::BLUE);
+            }
+            Tool::Wire => {
+                if let Some(from) = self.wire_from {
+                    // Red when letting go would be rejected
+                    let color = match graph.find_node_at_coords(&cursor.coords) {
+                        Some(to) if to != from && graph.check_wire(from, to).is_err() => Color::RED,
+                        _ => Color::BLUE,
+                    };
+                    let start = graph.node(from).coords;
+                    self.draw_pending_wire(d, graph, &start, &cursor.coords, color);
+                    graph.node(from).gate.draw(d, &start, color);
+                }
+            }
+            Tool::Select => {
+                if let Some(from) = self.select_from {
+                    let corner = Vector2::new(Coords::GRID_RADIUS, Coords::GRID_RADIUS);
+                    let min = Coords {
+                        x: from.x.min(cursor.coords.x),
+                        y: from.y.min(cursor.coords.y),
+                    };
+                    let max = Coords {
+                        x: from.x.max(cursor.coords.x),
+                        y: from.y.max(cursor.coords.y),
+                    };
+                    let top_left = min.into_position() - corner;
+                    let size = max.into_position() + corner - top_left;
+                    d.draw_rectangle_lines(
+                        top_left.x as i32,
+                        top_left.y as i32,
+                        size.x as i32,
+                        size.y as i32,
+                        Color::GOLD,
+                    );
+                }
+            }
+            Tool::Pan | Tool::Probe => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_in_box() {
+        let mut graph = Graph::new();
+        let gate = Gate::from_name("buffer").unwrap();
+        let inside = graph.add_node(&gate, &Coords { x: 1, y: 1 });
+        let edge = graph.add_node(&gate, &Coords { x: 3, y: 0 });
+        graph.add_node(&gate, &Coords { x: 4, y: 1 });
+        graph.add_node(&gate, &Coords { x: 1, y: -1 });
+
+        let a = Coords { x: 3, y: 2 };
+        let b = Coords { x: 0, y: 0 };
+        assert_eq!(nodes_in_box(&graph, &a, &b), vec![inside, edge]);
+        assert_eq!(nodes_in_box(&graph, &b, &a), vec![inside, edge]);
+    }
+
+    #[test]
+    fn test_switch_ends_chain() {
+        let mut tools = Tools::new();
+        tools.current_node = Some(NodeId(0));
+        tools.switch(Tool::Probe);
+        assert_eq!(tools.tool, Tool::Probe);
+        assert_eq!(tools.current_node, None);
+    }
+
+    #[test]
+    fn test_cancel_keeps_tool() {
+        let mut tools = Tools::new();
+        tools.current_node = Some(NodeId(0));
+        tools.cancel();
+        assert_eq!(tools.tool, Tool::Place);
+        assert_eq!(tools.current_node, None);
+    }
+
+    #[test]
+    fn test_drag_released_over_panel() {
+        let mut tools = Tools::new();
+        tools.current_node = Some(NodeId(2));
+        tools.wire_from = Some(NodeId(0));
+        tools.select_from = Some(Coords { x: 1, y: 1 });
+        tools.pan_from = Some(Vector2::new(4.0, 4.0));
+        tools.drop_drags();
+        assert_eq!(tools.wire_from, None);
+        assert_eq!(tools.select_from, None);
+        assert_eq!(tools.pan_from, None);
+        assert_eq!(tools.current_node, Some(NodeId(2)));
+    }
+}
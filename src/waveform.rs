@@ -0,0 +1,316 @@
+use std::collections::VecDeque;
+
+use raylib::prelude::*;
+
+use electron_architect_core::graph::{node::NodeId, sim::Simulation, Graph};
+
+use crate::{
+    cursor::Cursor,
+    input::{Input, InputHandler},
+};
+
+/// Docked along the bottom of the window, plotting the latest ticks of each probe.
+pub struct WaveformPanel {
+    /// Output of every node on each of the latest ticks, oldest first.
+    history: VecDeque<(u64, Vec<bool>)>,
+    /// Tick picked with the time cursor, or `None` while following the simulation.
+    scrubbed: Option<u64>,
+}
+
+impl WaveformPanel {
+    const MAX_TICKS: usize = 512;
+    const TICK_WIDTH: i32 = 4;
+    const LABEL_WIDTH: i32 = 80;
+    const PADDING: i32 = 6;
+    const ROW_HEIGHT: i32 = 16;
+    const TRACE_HEIGHT: i32 = 10;
+    const FONT_SIZE: i32 = 8;
+
+    pub fn new() -> Self {
+        Self {
+            history: VecDeque::new(),
+            scrubbed: None,
+        }
+    }
+
+    /// Call after every tick of the simulation.
+    pub fn sample(&mut self, simulation: &Simulation) {
+        // The simulation starts again when the graph is replaced
+        if self
+            .history
+            .back()
+            .is_some_and(|&(tick, _)| tick >= simulation.tick())
+        {
+            self.history.clear();
+            self.scrubbed = None;
+        }
+        if self.history.len() == Self::MAX_TICKS {
+            self.history.pop_front();
+        }
+        self.history
+            .push_back((simulation.tick(), simulation.state().to_vec()));
+
+        if let Some(tick) = self.scrubbed {
+            if self.history.front().is_some_and(|&(first, _)| tick < first) {
+                self.scrubbed = None;
+            }
+        }
+    }
+
+    /// Whether the time cursor has been placed. The simulation should be held meanwhile,
+    /// so the tick it's on isn't pushed out of the history.
+    pub fn is_scrubbing(&self) -> bool {
+        self.scrubbed.is_some()
+    }
+
+    /// Returns to following the simulation.
+    pub fn stop_scrubbing(&mut self) {
+        self.scrubbed = None;
+    }
+
+    /// Output of every node at the time cursor, if it's been placed.
+    pub fn scrubbed_state(&self) -> Option<(u64, &[bool])> {
+        let tick = self.scrubbed?;
+        self.history
+            .iter()
+            .find(|&&(t, _)| t == tick)
+            .map(|(tick, state)| (*tick, state.as_slice()))
+    }
+
+    /// Empty when there are no probes, so the panel is hidden.
+    pub fn bounding_box(num_probes: usize, window_width: i32, window_height: i32) -> Rectangle {
+        let height = match num_probes {
+            0 => 0,
+            n => n as i32 * Self::ROW_HEIGHT + 2 * Self::PADDING,
+        };
+        Rectangle {
+            x: 0.0,
+            y: (window_height - height) as f32,
+            width: window_width as f32,
+            height: height as f32,
+        }
+    }
+
+    fn trace_left(bounds: &Rectangle) -> i32 {
+        bounds.x as i32 + Self::PADDING + Self::LABEL_WIDTH
+    }
+
+    /// Index into the history of the leftmost tick shown, and how many ticks are shown.
+    fn visible(&self, bounds: &Rectangle) -> (usize, usize) {
+        let width = bounds.width as i32 - Self::LABEL_WIDTH - 2 * Self::PADDING;
+        let count = self
+            .history
+            .len()
+            .min((width / Self::TICK_WIDTH).max(0) as usize);
+        (self.history.len() - count, count)
+    }
+
+    fn tick_at(&self, x: f32, bounds: &Rectangle) -> Option<u64> {
+        let (start, count) = self.visible(bounds);
+        let offset = x as i32 - Self::trace_left(bounds);
+        if offset < 0 || offset / Self::TICK_WIDTH >= count as i32 {
+            return None;
+        }
+        Some(self.history[start + (offset / Self::TICK_WIDTH) as usize].0)
+    }
+
+    /// Dragging across the traces moves the time cursor, clicking the labels returns to live.
+    /// Returns whether the cursor is over the panel, in which case the editor should ignore it.
+    pub fn update(
+        &mut self,
+        rl: &RaylibHandle,
+        input: &InputHandler,
+        cursor: &Cursor,
+        bounds: &Rectangle,
+    ) -> bool {
+        self.update_at(cursor.pos, input.is_down(rl, &Input::Scrub), bounds)
+    }
+
+    fn update_at(&mut self, pos: Vector2, scrub: bool, bounds: &Rectangle) -> bool {
+        // Once the last probe is removed the panel is hidden, and with it the way back to live
+        if bounds.height <= 0.0 {
+            self.scrubbed = None;
+        }
+        let over = (bounds.x..bounds.x + bounds.width).contains(&pos.x)
+            && (bounds.y..bounds.y + bounds.height).contains(&pos.y);
+        if !over {
+            return false;
+        }
+        if scrub {
+            match self.tick_at(pos.x, bounds) {
+                Some(tick) => self.scrubbed = Some(tick),
+                None if (pos.x as i32) < Self::trace_left(bounds) => self.scrubbed = None,
+                None => {}
+            }
+        }
+        true
+    }
+
+    pub fn draw(
+        &self,
+        d: &mut RaylibDrawHandle,
+        graph: &Graph,
+        probes: &[NodeId],
+        bounds: &Rectangle,
+    ) {
+        if probes.is_empty() {
+            return;
+        }
+        d.draw_rectangle_rec(bounds, Color::BLACK.fade(0.9));
+        d.draw_line(
+            bounds.x as i32,
+            bounds.y as i32,
+            (bounds.x + bounds.width) as i32,
+            bounds.y as i32,
+            Color::DARKGRAY,
+        );
+
+        let left = Self::trace_left(bounds);
+        let (start, count) = self.visible(bounds);
+        for (row, &probe) in probes.iter().enumerate() {
+            let top = bounds.y as i32 + Self::PADDING + row as i32 * Self::ROW_HEIGHT;
+            d.draw_text(
+                &graph.node_label(probe),
+                bounds.x as i32 + Self::PADDING,
+                top + (Self::TRACE_HEIGHT - Self::FONT_SIZE) / 2,
+                Self::FONT_SIZE,
+                Color::LIGHTGRAY,
+            );
+
+            let level_y = |high: bool| match high {
+                true => top,
+                false => top + Self::TRACE_HEIGHT,
+            };
+            let mut previous: Option<bool> = None;
+            for (i, (_, state)) in self.history.range(start..start + count).enumerate() {
+                // Nodes added since this tick hadn't been simulated yet
+                let high = state.get(probe.0).copied().unwrap_or(false);
+                let x = left + i as i32 * Self::TICK_WIDTH;
+                if previous.is_some_and(|previous| previous != high) {
+                    d.draw_line(x, level_y(false), x, level_y(true), Color::LIME);
+                }
+                d.draw_line(
+                    x,
+                    level_y(high),
+                    x + Self::TICK_WIDTH,
+                    level_y(high),
+                    Color::LIME,
+                );
+                previous = Some(high);
+            }
+        }
+
+        if let Some((tick, _)) = self.scrubbed_state() {
+            let i = self.history.range(start..).position(|&(t, _)| t == tick);
+            if let Some(i) = i {
+                let x = left + i as i32 * Self::TICK_WIDTH + Self::TICK_WIDTH / 2;
+                d.draw_line(
+                    x,
+                    bounds.y as i32,
+                    x,
+                    (bounds.y + bounds.height) as i32,
+                    Color::GOLD,
+                );
+                d.draw_text(
+                    &tick.to_string(),
+                    x + 2,
+                    bounds.y as i32 + 2,
+                    Self::FONT_SIZE,
+                    Color::GOLD,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use electron_architect_core::{
+        coords::Coords,
+        graph::{
+            elbow::Elbow,
+            gate::{Gate, GateN},
+        },
+    };
+
+    fn oscillator() -> Graph {
+        let mut graph = Graph::new();
+        let nor = graph.add_node(&Gate::GN(GateN::Nor), &Coords::default());
+        graph.add_wire(nor, nor, &Elbow::HorzS);
+        graph
+    }
+
+    #[test]
+    fn test_history() {
+        let graph = oscillator();
+        let mut simulation = Simulation::new();
+        let mut panel = WaveformPanel::new();
+        for _ in 0..WaveformPanel::MAX_TICKS + 10 {
+            simulation.step(&graph);
+            panel.sample(&simulation);
+        }
+        assert_eq!(panel.history.len(), WaveformPanel::MAX_TICKS);
+        assert_eq!(panel.history.front().unwrap().0, 11);
+
+        simulation.reset();
+        simulation.step(&graph);
+        panel.sample(&simulation);
+        assert_eq!(panel.history.len(), 1);
+    }
+
+    #[test]
+    fn test_tick_at() {
+        let graph = oscillator();
+        let mut simulation = Simulation::new();
+        let mut panel = WaveformPanel::new();
+        for _ in 0..10 {
+            simulation.step(&graph);
+            panel.sample(&simulation);
+        }
+
+        let bounds = WaveformPanel::bounding_box(1, 1280, 720);
+        assert_eq!(bounds.height, 28.0);
+        let left = WaveformPanel::trace_left(&bounds) as f32;
+        assert_eq!(panel.tick_at(left, &bounds), Some(1));
+        assert_eq!(panel.tick_at(left + 9.0, &bounds), Some(3));
+        assert_eq!(panel.tick_at(left - 1.0, &bounds), None);
+        assert_eq!(panel.tick_at(left + 40.0, &bounds), None);
+
+        // Too narrow to show every tick, so the oldest are cut off
+        let narrow = WaveformPanel::bounding_box(1, 100, 720);
+        assert_eq!(panel.visible(&narrow), (8, 2));
+        assert_eq!(
+            panel.tick_at(WaveformPanel::trace_left(&narrow) as f32, &narrow),
+            Some(9)
+        );
+
+        panel.scrubbed = Some(4);
+        assert_eq!(panel.scrubbed_state(), Some((4, &[false][..])));
+        assert!(panel.is_scrubbing());
+        panel.stop_scrubbing();
+        assert_eq!(panel.scrubbed_state(), None);
+    }
+
+    #[test]
+    fn test_last_probe_removed() {
+        let graph = oscillator();
+        let mut simulation = Simulation::new();
+        let mut panel = WaveformPanel::new();
+        for _ in 0..10 {
+            simulation.step(&graph);
+            panel.sample(&simulation);
+        }
+
+        let bounds = WaveformPanel::bounding_box(1, 1280, 720);
+        let pos = Vector2::new(WaveformPanel::trace_left(&bounds) as f32, bounds.y + 1.0);
+        assert!(panel.update_at(pos, true, &bounds));
+        assert_eq!(panel.scrubbed, Some(1));
+        assert!(panel.update_at(pos, false, &bounds));
+        assert!(panel.is_scrubbing());
+
+        let hidden = WaveformPanel::bounding_box(0, 1280, 720);
+        assert!(!panel.update_at(pos, false, &hidden));
+        assert!(!panel.is_scrubbing());
+    }
+}